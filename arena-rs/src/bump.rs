@@ -0,0 +1,169 @@
+//! Bump allocator.
+//!
+//! Memory comes in chunks from the global allocator, each chunk twice as
+//! large as the one before. Allocating just aligns the pointer into the
+//! current chunk and moves it past the new value, starting a new chunk
+//! when the current one is full. Nothing is freed until the arena is
+//! reset or dropped, and values are never dropped at all, so types owning
+//! resources leak them: such values belong in a `TypedArena`.
+
+use std::alloc::{self, Layout};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::ptr::{self, NonNull};
+
+const MIN_CHUNK_SIZE: usize = 4096;
+const CHUNK_ALIGN: usize = 16;
+
+struct Chunk {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+pub struct Arena {
+    chunks: RefCell<Vec<Chunk>>,
+    /// Free space of the last chunk.
+    ptr: Cell<*mut u8>,
+    end: Cell<*mut u8>,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            ptr: Cell::new(ptr::null_mut()),
+            end: Cell::new(ptr::null_mut()),
+        }
+    }
+
+    /// Moves `value` into the arena. It is never dropped.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        let p = self.alloc_layout(Layout::new::<T>()).cast::<T>().as_ptr();
+        // SAFETY: memory is fresh, aligned and large enough for T
+        unsafe {
+            p.write(value);
+            &mut *p
+        }
+    }
+
+    /// Copies a slice into the arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice<T: Clone>(&self, src: &[T]) -> &mut [T] {
+        let layout = Layout::array::<T>(src.len()).expect("slice too large");
+        let p = self.alloc_layout(layout).cast::<T>().as_ptr();
+        // SAFETY: memory is fresh, aligned and large enough for the
+        // slice, all of it written before the slice is made. If clone
+        // panics, the slice is never made.
+        unsafe {
+            for (i, x) in src.iter().enumerate() {
+                p.add(i).write(x.clone());
+            }
+            std::slice::from_raw_parts_mut(p, src.len())
+        }
+    }
+
+    /// Copies a string into the arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_str(&self, src: &str) -> &mut str {
+        let bytes = self.alloc_slice(src.as_bytes());
+        // SAFETY: bytes are a copy of a str
+        unsafe { std::str::from_utf8_unchecked_mut(bytes) }
+    }
+
+    /// Uninitialized memory for `layout`, valid while the arena lives.
+    pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        if layout.size() == 0 {
+            // aligned, never read or written
+            return NonNull::new(layout.align() as *mut u8).unwrap();
+        }
+        if let Some(p) = self.bump(layout) {
+            return p;
+        }
+        self.grow(layout);
+        self.bump(layout).unwrap()
+    }
+
+    fn bump(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let ptr = self.ptr.get();
+        let free = self.end.get() as usize - ptr as usize;
+        let pad = ptr.align_offset(layout.align());
+        if pad > free || layout.size() > free - pad {
+            return None;
+        }
+        // SAFETY: both offsets stay within the chunk, checked above
+        unsafe {
+            let start = ptr.add(pad);
+            self.ptr.set(start.add(layout.size()));
+            Some(NonNull::new_unchecked(start))
+        }
+    }
+
+    /// Starts a chunk that fits `layout`.
+    fn grow(&self, layout: Layout) {
+        let mut chunks = self.chunks.borrow_mut();
+        let size = chunks
+            .last()
+            .map_or(MIN_CHUNK_SIZE, |c| c.layout.size() * 2)
+            .max(layout.size());
+        let chunk_layout = Layout::from_size_align(size, layout.align().max(CHUNK_ALIGN))
+            .expect("allocation too large");
+        // SAFETY: size is not zero
+        let ptr = unsafe { alloc::alloc(chunk_layout) };
+        let Some(ptr) = NonNull::new(ptr) else {
+            alloc::handle_alloc_error(chunk_layout);
+        };
+        chunks.push(Chunk {
+            ptr,
+            layout: chunk_layout,
+        });
+        self.ptr.set(ptr.as_ptr());
+        // SAFETY: end of the same allocation
+        self.end.set(unsafe { ptr.as_ptr().add(size) });
+    }
+
+    /// Frees all values, keeping the largest chunk for reuse.
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        let Some(last) = chunks.pop() else {
+            return;
+        };
+        for chunk in chunks.drain(..) {
+            // SAFETY: chunk was allocated with this layout
+            unsafe { alloc::dealloc(chunk.ptr.as_ptr(), chunk.layout) };
+        }
+        self.ptr.set(last.ptr.as_ptr());
+        // SAFETY: end of the same allocation
+        self.end
+            .set(unsafe { last.ptr.as_ptr().add(last.layout.size()) });
+        chunks.push(last);
+    }
+
+    /// Bytes held in chunks, used or not.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(|c| c.layout.size()).sum()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        for chunk in self.chunks.get_mut().drain(..) {
+            // SAFETY: chunk was allocated with this layout
+            unsafe { alloc::dealloc(chunk.ptr.as_ptr(), chunk.layout) };
+        }
+    }
+}
+
+impl fmt::Debug for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish()
+    }
+}
@@ -0,0 +1,16 @@
+//! Arenas: allocate many values cheaply and free them all at once.
+//!
+//! [`Arena`] is a bump allocator for values of any type. It hands out
+//! memory from large chunks by moving a pointer, and never drops the
+//! values. [`TypedArena`] holds values of one type and drops them with
+//! the arena. Both give out references living as long as the arena, so
+//! nodes of graphs, trees and lists can point to each other with plain
+//! references, and the whole structure goes away in one step.
+//!
+//! See https://en.wikipedia.org/wiki/Region-based_memory_management
+
+mod bump;
+mod typed;
+
+pub use bump::Arena;
+pub use typed::TypedArena;
@@ -0,0 +1,111 @@
+//! Arena of values of one type, dropped with the arena.
+//!
+//! Values live in a list of vectors, each with twice the capacity of the
+//! one before. A vector is only pushed to while it has spare capacity,
+//! so it never reallocates and references into it stay valid. Dropping
+//! the vectors drops the values, and since they are plain vectors, the
+//! values may refer to each other, even in cycles, as long as their type
+//! doesn't implement `Drop` itself: such a `Drop` could follow references
+//! to values dropped already. Fields with their own `Drop` are fine.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+
+/// Bytes in the first chunk.
+const MIN_CHUNK_SIZE: usize = 4096;
+
+pub struct TypedArena<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+}
+
+impl<T> Default for TypedArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TypedArena<T> {
+    pub fn new() -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Moves `value` into the arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&self, value: T) -> &mut T {
+        let mut chunks = self.chunks.borrow_mut();
+        let chunk = Self::chunk_with_room(&mut chunks, 1);
+        chunk.push(value);
+        // SAFETY: the chunk never reallocates and the value is never
+        // handed out again
+        unsafe { &mut *chunk.as_mut_ptr().add(chunk.len() - 1) }
+    }
+
+    /// Moves all values of `iter` into the arena, next to each other.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_extend(&self, iter: impl IntoIterator<Item = T>) -> &mut [T] {
+        // iterator may allocate in the arena itself, collect before
+        // borrowing the chunks
+        let values: Vec<T> = iter.into_iter().collect();
+        if values.is_empty() {
+            return &mut [];
+        }
+        let mut chunks = self.chunks.borrow_mut();
+        let chunk = Self::chunk_with_room(&mut chunks, values.len());
+        let start = chunk.len();
+        chunk.extend(values);
+        // SAFETY: same as in `alloc`
+        unsafe {
+            std::slice::from_raw_parts_mut(chunk.as_mut_ptr().add(start), chunk.len() - start)
+        }
+    }
+
+    /// Last chunk if it has room for `n` more values, else a new one.
+    fn chunk_with_room(chunks: &mut Vec<Vec<T>>, n: usize) -> &mut Vec<T> {
+        let cap = match chunks.last() {
+            Some(c) if c.capacity() - c.len() >= n => None,
+            Some(c) => Some(c.capacity() * 2),
+            None => Some((MIN_CHUNK_SIZE / mem::size_of::<T>().max(1)).max(1)),
+        };
+        if let Some(cap) = cap {
+            chunks.push(Vec::with_capacity(cap.max(n)));
+        }
+        chunks.last_mut().unwrap()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all values, keeping the largest chunk for reuse.
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        if let Some(mut last) = chunks.pop() {
+            chunks.clear();
+            last.clear();
+            chunks.push(last);
+        }
+    }
+
+    /// Values in allocation order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.chunks.get_mut().iter_mut().flatten()
+    }
+
+    /// Values in allocation order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.chunks.into_inner().into_iter().flatten().collect()
+    }
+}
+
+impl<T> fmt::Debug for TypedArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TypedArena").field("len", &self.len()).finish()
+    }
+}
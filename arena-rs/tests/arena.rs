@@ -0,0 +1,199 @@
+//! Values allocated across many chunks keep their contents, `TypedArena`
+//! drops every value exactly once, on reset or with the arena, and the
+//! bump `Arena` never does. Small enough to run under Miri.
+
+use std::alloc::Layout;
+use std::cell::{Cell, RefCell};
+
+use arena::{Arena, TypedArena};
+
+/// Enough values to fill several chunks.
+const N: usize = if cfg!(miri) { 2000 } else { 100_000 };
+
+/// Counts its drops in a shared counter.
+struct Counted<'a>(&'a Cell<usize>, usize);
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn typed_drops_every_value_once() {
+    let drops = Cell::new(0);
+    let arena = TypedArena::new();
+    let mut refs = Vec::new();
+    for i in 0..N {
+        refs.push(&*arena.alloc(Counted(&drops, i)));
+    }
+    let slice = arena.alloc_extend((N..N + 100).map(|i| Counted(&drops, i)));
+    assert_eq!(slice.len(), 100);
+    // references stay valid as chunks are added
+    for (i, r) in refs.iter().enumerate() {
+        assert_eq!(r.1, i);
+    }
+    assert_eq!(arena.len(), N + 100);
+    assert_eq!(drops.get(), 0);
+    drop(arena);
+    assert_eq!(drops.get(), N + 100);
+}
+
+#[test]
+fn typed_reset_drops_and_reuses() {
+    let drops = Cell::new(0);
+    let mut arena = TypedArena::new();
+    for round in 1..=3 {
+        for i in 0..N {
+            arena.alloc(Counted(&drops, i));
+        }
+        assert_eq!(arena.len(), N);
+        arena.reset();
+        assert!(arena.is_empty());
+        assert_eq!(drops.get(), round * N);
+    }
+    // reset of an empty arena is fine
+    arena.reset();
+    arena.reset();
+    assert_eq!(drops.get(), 3 * N);
+    let v = arena.alloc(Counted(&drops, 7));
+    assert_eq!(v.1, 7);
+    drop(arena);
+    assert_eq!(drops.get(), 3 * N + 1);
+}
+
+#[test]
+fn typed_into_vec_and_iter_mut_keep_order() {
+    let drops = Cell::new(0);
+    let mut arena = TypedArena::new();
+    for i in 0..N {
+        arena.alloc(Counted(&drops, i));
+    }
+    for c in arena.iter_mut() {
+        c.1 *= 2;
+    }
+    let v = arena.into_vec();
+    assert_eq!(drops.get(), 0);
+    assert!(v.iter().map(|c| c.1).eq((0..N).map(|i| i * 2)));
+    drop(v);
+    assert_eq!(drops.get(), N);
+}
+
+/// Node of a cyclic graph, which a typed arena allows.
+struct Node<'a> {
+    next: Cell<Option<&'a Node<'a>>>,
+    name: String,
+}
+
+#[test]
+fn typed_cycles() {
+    let arena = TypedArena::new();
+    let a = arena.alloc(Node {
+        next: Cell::new(None),
+        name: "a".to_string(),
+    });
+    let b = arena.alloc(Node {
+        next: Cell::new(None),
+        name: "b".to_string(),
+    });
+    b.next.set(Some(a));
+    a.next.set(Some(b));
+    let a = &*a;
+    assert_eq!(a.next.get().unwrap().next.get().unwrap().name, "a");
+}
+
+#[test]
+fn bump_never_drops() {
+    let drops = Cell::new(0);
+    let arena = Arena::new();
+    for i in 0..N {
+        arena.alloc(Counted(&drops, i));
+    }
+    drop(arena);
+    assert_eq!(drops.get(), 0);
+}
+
+#[test]
+fn bump_values_keep_contents() {
+    let arena = Arena::new();
+    let mut bytes = Vec::new();
+    let mut words = Vec::new();
+    let mut strs = Vec::new();
+    for i in 0..N {
+        // mixed sizes and alignments
+        bytes.push(&*arena.alloc(i as u8));
+        words.push(&*arena.alloc(i as u128));
+        if i % 100 == 0 {
+            strs.push(&*arena.alloc_str(&"x".repeat(i / 100)));
+        }
+    }
+    let big = arena.alloc_slice(&vec![7u64; 10_000]);
+    big[9_999] = 8;
+    for i in 0..N {
+        assert_eq!(*bytes[i], i as u8);
+        assert_eq!(*words[i], i as u128);
+        assert_eq!(words[i] as *const u128 as usize % 16, 0);
+    }
+    for (i, s) in strs.iter().enumerate() {
+        assert_eq!(s.len(), i);
+    }
+    assert_eq!(big.iter().sum::<u64>(), 7 * 10_000 + 1);
+    let zst = arena.alloc_layout(Layout::from_size_align(0, 64).unwrap());
+    assert_eq!(zst.as_ptr() as usize % 64, 0);
+    let aligned = arena.alloc_layout(Layout::from_size_align(10, 4096).unwrap());
+    assert_eq!(aligned.as_ptr() as usize % 4096, 0);
+}
+
+#[test]
+fn bump_reset_keeps_largest_chunk() {
+    let mut arena = Arena::new();
+    assert_eq!(arena.allocated_bytes(), 0);
+    arena.reset();
+    for _ in 0..N {
+        arena.alloc([0u8; 32]);
+    }
+    let before = arena.allocated_bytes();
+    assert!(before >= N * 32);
+    arena.reset();
+    let kept = arena.allocated_bytes();
+    assert!(
+        kept < before && kept >= before / 2,
+        "{} of {}",
+        kept,
+        before
+    );
+    // the kept chunk is reused, not grown
+    for _ in 0..kept / 32 {
+        arena.alloc([1u8; 32]);
+    }
+    assert_eq!(arena.allocated_bytes(), kept);
+    arena.alloc(1u8);
+    assert!(arena.allocated_bytes() > kept);
+}
+
+#[test]
+fn slice_clone_panic_is_not_dropped() {
+    let drops = RefCell::new(Vec::new());
+    struct Bomb<'a>(&'a RefCell<Vec<usize>>, usize);
+    impl Clone for Bomb<'_> {
+        fn clone(&self) -> Self {
+            assert!(self.1 != 3, "boom");
+            Bomb(self.0, self.1)
+        }
+    }
+    impl Drop for Bomb<'_> {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push(self.1);
+        }
+    }
+    let src: Vec<Bomb> = (0..5).map(|i| Bomb(&drops, i)).collect();
+    let arena = Arena::new();
+    let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        arena.alloc_slice(&src);
+    }));
+    assert!(r.is_err());
+    // the clones made before the panic leak, nothing is dropped twice
+    assert!(drops.borrow().is_empty());
+    drop(src);
+    assert_eq!(*drops.borrow(), [0, 1, 2, 3, 4]);
+}
@@ -0,0 +1,330 @@
+//! AVL tree: a binary search tree kept balanced by rotations.
+//!
+//! Every node stores the height of its subtree. After an insert or a
+//! remove, each node on the way back up to the root has its height
+//! updated, and if the heights of its two subtrees differ by more than
+//! one, it is rotated back into balance. The tree height then stays under
+//! 1.44 log2(n), so even values inserted in sorted order cost O(log n)
+//! each, instead of degenerating into a linked list.
+//!
+//! See https://en.wikipedia.org/wiki/AVL_tree
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    left: Link<T>,
+    right: Link<T>,
+    /// Height of the subtree, 1 for a leaf.
+    height: u8,
+}
+
+fn height<T>(link: &Link<T>) -> u8 {
+    link.as_ref().map_or(0, |n| n.height)
+}
+
+impl<T> Node<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            left: None,
+            right: None,
+            height: 1,
+        }
+    }
+
+    fn update_height(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+    }
+
+    /// Left subtree height minus right subtree height.
+    fn balance_factor(&self) -> i32 {
+        height(&self.left) as i32 - height(&self.right) as i32
+    }
+}
+
+//     n            l
+//    / \          / \
+//   l   c  ->    a   n
+//  / \              / \
+// a   b            b   c
+fn rotate_right<T>(mut n: Box<Node<T>>) -> Box<Node<T>> {
+    let mut l = n.left.take().expect("rotating right without left child");
+    n.left = l.right.take();
+    n.update_height();
+    l.right = Some(n);
+    l.update_height();
+    l
+}
+
+fn rotate_left<T>(mut n: Box<Node<T>>) -> Box<Node<T>> {
+    let mut r = n.right.take().expect("rotating left without right child");
+    n.right = r.left.take();
+    n.update_height();
+    r.left = Some(n);
+    r.update_height();
+    r
+}
+
+/// Restores balance of a node whose subtrees are balanced and differ in
+/// height by at most two.
+fn balanced<T>(mut n: Box<Node<T>>) -> Box<Node<T>> {
+    n.update_height();
+    let bf = n.balance_factor();
+    if bf > 1 {
+        // left-right case turns into left-left
+        if n.left.as_ref().is_some_and(|l| l.balance_factor() < 0) {
+            n.left = n.left.take().map(rotate_left);
+        }
+        rotate_right(n)
+    } else if bf < -1 {
+        if n.right.as_ref().is_some_and(|r| r.balance_factor() > 0) {
+            n.right = n.right.take().map(rotate_right);
+        }
+        rotate_left(n)
+    } else {
+        n
+    }
+}
+
+fn rebalance<T>(link: &mut Link<T>) {
+    *link = link.take().map(balanced);
+}
+
+fn insert<T: Ord>(link: &mut Link<T>, value: T) -> bool {
+    let Some(node) = link else {
+        *link = Some(Box::new(Node::new(value)));
+        return true;
+    };
+    let inserted = match value.cmp(&node.value) {
+        Ordering::Less => insert(&mut node.left, value),
+        Ordering::Greater => insert(&mut node.right, value),
+        Ordering::Equal => false,
+    };
+    if inserted {
+        rebalance(link);
+    }
+    inserted
+}
+
+/// Detaches the leftmost node of a non-empty subtree.
+fn remove_min<T>(link: &mut Link<T>) -> Box<Node<T>> {
+    let node = link.as_mut().expect("removing min of empty subtree");
+    if node.left.is_some() {
+        let min = remove_min(&mut node.left);
+        rebalance(link);
+        min
+    } else {
+        let mut node = link.take().unwrap();
+        *link = node.right.take();
+        node
+    }
+}
+
+fn remove<T, Q>(link: &mut Link<T>, key: &Q) -> Option<T>
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let node = link.as_mut()?;
+    let removed = match key.cmp(node.value.borrow()) {
+        Ordering::Less => remove(&mut node.left, key)?,
+        Ordering::Greater => remove(&mut node.right, key)?,
+        Ordering::Equal => {
+            let mut node = link.take().unwrap();
+            *link = match (node.left.take(), node.right.take()) {
+                (None, child) | (child, None) => child,
+                // replace by the successor
+                (left, mut right) => {
+                    let mut succ = remove_min(&mut right);
+                    succ.left = left;
+                    succ.right = right;
+                    Some(balanced(succ))
+                }
+            };
+            return Some(node.value);
+        }
+    };
+    rebalance(link);
+    Some(removed)
+}
+
+/// Ordered set.
+pub struct AvlTree<T> {
+    root: Link<T>,
+    len: usize,
+}
+
+impl<T> Default for AvlTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> AvlTree<T> {
+    pub const fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of nodes on the longest path from the root, 0 if empty.
+    pub fn height(&self) -> usize {
+        height(&self.root) as usize
+    }
+
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.len = 0;
+    }
+
+    /// Smallest value.
+    pub fn first(&self) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    /// Largest value.
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    /// Values in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut it = Iter {
+            stack: Vec::with_capacity(self.height()),
+            len: self.len,
+        };
+        it.push_left(self.root.as_deref());
+        it
+    }
+}
+
+impl<T: Ord> AvlTree<T> {
+    /// Inserts `value`, false if an equal value is already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        let inserted = insert(&mut self.root, value);
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    /// Removes and returns the value equal to `key`.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let removed = remove(&mut self.root, key);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Value equal to `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            match key.cmp(node.value.borrow()) {
+                Ordering::Less => link = &node.left,
+                Ordering::Greater => link = &node.right,
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+        None
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+pub struct Iter<'a, T> {
+    /// Nodes whose value and right subtree are yet to be visited.
+    stack: Vec<&'a Node<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a AvlTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Extend<T> for AvlTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for AvlTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AvlTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self).finish()
+    }
+}
@@ -0,0 +1,89 @@
+//! Height stays within the AVL bound of 1.44 log2(n + 2) after sorted,
+//! reversed and random inserts and removals, and contents match a
+//! `BTreeSet`.
+
+use std::collections::BTreeSet;
+
+use avl::AvlTree;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn assert_balanced<T>(tree: &AvlTree<T>) {
+    let bound = 1.44 * ((tree.len() + 2) as f64).log2();
+    assert!(
+        tree.height() as f64 <= bound,
+        "height {} of {} nodes over {:.2}",
+        tree.height(),
+        tree.len(),
+        bound
+    );
+}
+
+#[test]
+fn sorted_inserts_stay_balanced() {
+    for n in [0, 1, 2, 3, 7, 8, 100, 1000, 100_000] {
+        let mut up = AvlTree::new();
+        let mut down = AvlTree::new();
+        for i in 0..n {
+            assert!(up.insert(i));
+            assert!(down.insert(n - i));
+            if i % 997 == 0 {
+                assert_balanced(&up);
+                assert_balanced(&down);
+            }
+        }
+        assert_balanced(&up);
+        assert_balanced(&down);
+        assert_eq!(up.len(), n);
+        assert!(up.iter().copied().eq(0..n));
+        assert!(down.iter().copied().eq(1..=n));
+        // removing from one end, the tree keeps rebalancing
+        for i in 0..n / 2 {
+            assert_eq!(up.remove(&i), Some(i));
+        }
+        assert_balanced(&up);
+        assert_eq!(up.first(), (n > 0).then_some(&(n / 2)));
+    }
+}
+
+#[test]
+fn random_edits_against_btreeset() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let mut tree = AvlTree::new();
+    let mut model = BTreeSet::new();
+    for step in 0..50_000 {
+        let x = rng.below(5000);
+        if rng.below(3) == 0 {
+            assert_eq!(tree.remove(&x), model.take(&x));
+        } else {
+            assert_eq!(tree.insert(x), model.insert(x));
+        }
+        assert_eq!(tree.contains(&x), model.contains(&x));
+        if step % 1000 == 0 {
+            assert_balanced(&tree);
+            assert_eq!(tree.len(), model.len());
+            assert!(tree.iter().eq(model.iter()));
+            assert_eq!(tree.first(), model.first());
+            assert_eq!(tree.last(), model.last());
+        }
+    }
+    assert_balanced(&tree);
+    while let Some(&x) = model.iter().next() {
+        assert_eq!(tree.remove(&x), model.take(&x));
+    }
+    assert!(tree.is_empty());
+    assert_eq!(tree.height(), 0);
+}
@@ -0,0 +1,76 @@
+//! Long division, Knuth's algorithm D.
+//!
+//! Schoolbook division guessing one limb of the quotient at a time from
+//! the top two limbs of the remainder and the top limb of the divisor.
+//! With the divisor shifted so its top bit is set, the guess is at most
+//! 2 too large, and checking it against the second limb of the divisor
+//! makes it off by one only rarely, in which case the divisor is added
+//! back once.
+//!
+//! See Knuth, TAOCP vol. 2, 4.3.1, and Hacker's Delight, 9-2
+
+use crate::limbs::{self, DoubleLimb, Limb, LIMB_BITS};
+
+/// Normalized quotient and remainder. Panics if `v` is zero.
+pub fn div_rem(u: &[Limb], v: &[Limb]) -> (Vec<Limb>, Vec<Limb>) {
+    let (u, v) = (limbs::trim(u), limbs::trim(v));
+    assert!(!v.is_empty(), "division by zero");
+    if limbs::cmp(u, v).is_lt() {
+        return (Vec::new(), u.to_vec());
+    }
+    if v.len() == 1 {
+        let mut q = u.to_vec();
+        let r = limbs::div_rem_small(&mut q, v[0]);
+        limbs::normalize(&mut q);
+        let r = if r == 0 { Vec::new() } else { vec![r] };
+        return (q, r);
+    }
+
+    let base: DoubleLimb = 1 << LIMB_BITS;
+    let shift = v[v.len() - 1].leading_zeros();
+    let mut vn = limbs::shl(v, shift as usize);
+    // nothing is shifted out of the top limb
+    vn.pop();
+    let mut un = limbs::shl(u, shift as usize);
+    let n = vn.len();
+    let m = u.len() - n;
+    let (v1, v2) = (vn[n - 1] as DoubleLimb, vn[n - 2] as DoubleLimb);
+    let mut q = vec![0; m + 1];
+    for j in (0..=m).rev() {
+        let top = ((un[j + n] as DoubleLimb) << LIMB_BITS) | un[j + n - 1] as DoubleLimb;
+        let mut qhat = top / v1;
+        let mut rhat = top % v1;
+        while qhat >= base || qhat * v2 > ((rhat << LIMB_BITS) | un[j + n - 2] as DoubleLimb) {
+            qhat -= 1;
+            rhat += v1;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // un[j..=j+n] -= qhat * vn
+        let mut borrow: i64 = 0;
+        for i in 0..n {
+            let p = qhat * vn[i] as DoubleLimb;
+            let t = un[i + j] as i64 - borrow - (p as Limb) as i64;
+            un[i + j] = t as Limb;
+            borrow = (p >> LIMB_BITS) as i64 - (t >> LIMB_BITS);
+        }
+        let t = un[j + n] as i64 - borrow;
+        un[j + n] = t as Limb;
+
+        if t < 0 {
+            // guess was one too large, add the divisor back
+            qhat -= 1;
+            let carry = limbs::add_assign(&mut un[j..j + n], &vn);
+            un[j + n] = un[j + n].wrapping_add(carry as Limb);
+        }
+        q[j] = qhat as Limb;
+    }
+
+    un.truncate(n);
+    limbs::shr_small(&mut un, shift);
+    limbs::normalize(&mut un);
+    limbs::normalize(&mut q);
+    (q, un)
+}
@@ -0,0 +1,249 @@
+//! Signed big integer, sign and magnitude.
+//!
+//! Division truncates toward zero and the remainder takes the sign of the
+//! dividend, as with Rust's primitive integers.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+use crate::{BigUint, ParseBigIntError};
+
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// Never set for zero.
+    neg: bool,
+    mag: BigUint,
+}
+
+impl BigInt {
+    fn new(neg: bool, mag: BigUint) -> Self {
+        Self {
+            neg: neg && !mag.is_zero(),
+            mag,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    /// Absolute value.
+    pub fn magnitude(&self) -> &BigUint {
+        &self.mag
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.mag
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from(self.mag.clone())
+    }
+
+    /// Value as i128 if it fits.
+    pub fn to_i128(&self) -> Option<i128> {
+        let mag = self.mag.to_u128()?;
+        if self.neg {
+            0i128.checked_sub_unsigned(mag)
+        } else {
+            i128::try_from(mag).ok()
+        }
+    }
+
+    /// Parses digits with an optional sign.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        let (neg, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        BigUint::from_str_radix(digits, radix).map(|mag| Self::new(neg, mag))
+    }
+
+    /// Digits in `radix`, lowercase, with a '-' if negative.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = self.mag.to_str_radix(radix);
+        if self.neg {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    /// Truncated quotient and remainder. Panics if `other` is zero.
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = self.mag.div_rem(&other.mag);
+        (Self::new(self.neg != other.neg, q), Self::new(self.neg, r))
+    }
+
+    pub fn pow(&self, exp: u32) -> Self {
+        Self::new(self.neg && exp % 2 == 1, self.mag.pow(exp))
+    }
+
+    /// Greatest common divisor of the magnitudes, never negative.
+    pub fn gcd(&self, other: &Self) -> Self {
+        Self::from(self.mag.gcd(&other.mag))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(mag: BigUint) -> Self {
+        Self { neg: false, mag }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(x: $t) -> Self {
+                Self::new(x < 0, BigUint::from(x.unsigned_abs()))
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(x: $t) -> Self {
+                Self::from(BigUint::from(x))
+            }
+        }
+    )*};
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize);
+impl_from_uint!(u8, u16, u32, u64, u128, usize);
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, false) => self.mag.cmp(&other.mag),
+            (true, true) => other.mag.cmp(&self.mag),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.neg, self.mag.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.neg, self.mag)
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.neg == other.neg {
+            return BigInt::new(self.neg, &self.mag + &other.mag);
+        }
+        // signs differ, the larger magnitude wins
+        match self.mag.cmp(&other.mag) {
+            Ordering::Less => BigInt::new(other.neg, &other.mag - &self.mag),
+            _ => BigInt::new(self.neg, &self.mag - &other.mag),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(self.neg != other.neg, &self.mag * &other.mag)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+forward_binop!(BigInt, Add, add, AddAssign, add_assign);
+forward_binop!(BigInt, Sub, sub, SubAssign, sub_assign);
+forward_binop!(BigInt, Mul, mul, MulAssign, mul_assign);
+forward_binop!(BigInt, Div, div, DivAssign, div_assign);
+forward_binop!(BigInt, Rem, rem, RemAssign, rem_assign);
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parses decimal digits with an optional sign.
+    fn from_str(s: &str) -> Result<Self, ParseBigIntError> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.neg, "", &self.mag.to_str_radix(10))
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.neg, "0x", &self.mag.to_str_radix(16))
+    }
+}
+
+impl fmt::UpperHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.neg, "0x", &self.mag.to_str_radix(16).to_uppercase())
+    }
+}
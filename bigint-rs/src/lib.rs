@@ -0,0 +1,64 @@
+//! Arbitrary-precision integers.
+//!
+//! [`BigUint`] is a vector of 32-bit limbs, least significant first, with
+//! no zero limbs at the top, so every number has one representation and
+//! zero has no limbs at all. [`BigInt`] is a sign and a `BigUint`
+//! magnitude.
+//!
+//! Multiplication switches from schoolbook to Karatsuba for long
+//! numbers, division is Knuth's algorithm D.
+//!
+//! See https://en.wikipedia.org/wiki/Arbitrary-precision_arithmetic
+
+/// Implements the owned variants of a binary operator and its assign
+/// operator through the one on references.
+macro_rules! forward_binop {
+    ($t:ty, $op:ident, $f:ident, $op_assign:ident, $f_assign:ident) => {
+        impl std::ops::$op<$t> for $t {
+            type Output = $t;
+
+            fn $f(self, other: $t) -> $t {
+                std::ops::$op::$f(&self, &other)
+            }
+        }
+
+        impl std::ops::$op<&$t> for $t {
+            type Output = $t;
+
+            fn $f(self, other: &$t) -> $t {
+                std::ops::$op::$f(&self, other)
+            }
+        }
+
+        impl std::ops::$op<$t> for &$t {
+            type Output = $t;
+
+            fn $f(self, other: $t) -> $t {
+                std::ops::$op::$f(self, &other)
+            }
+        }
+
+        impl std::ops::$op_assign<&$t> for $t {
+            fn $f_assign(&mut self, other: &$t) {
+                *self = std::ops::$op::$f(&*self, other);
+            }
+        }
+
+        impl std::ops::$op_assign<$t> for $t {
+            fn $f_assign(&mut self, other: $t) {
+                *self = std::ops::$op::$f(&*self, &other);
+            }
+        }
+    };
+}
+
+mod div;
+mod int;
+mod limbs;
+mod mul;
+mod radix;
+mod uint;
+
+pub use int::BigInt;
+pub use radix::ParseBigIntError;
+pub use uint::BigUint;
@@ -0,0 +1,142 @@
+//! Arithmetic on little-endian slices of 32-bit limbs.
+//!
+//! Limbs are 32 bits so that a product of two, plus carries, fits in a
+//! u64. Functions don't require normalized input, i.e. there may be zero
+//! limbs at the top, unless said otherwise.
+
+use std::cmp::Ordering;
+
+pub type Limb = u32;
+pub type DoubleLimb = u64;
+pub const LIMB_BITS: u32 = Limb::BITS;
+
+/// Drops zero limbs at the top.
+pub fn normalize(v: &mut Vec<Limb>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+/// Slice without zero limbs at the top.
+pub fn trim(v: &[Limb]) -> &[Limb] {
+    let len = v.iter().rposition(|&x| x != 0).map_or(0, |i| i + 1);
+    &v[..len]
+}
+
+pub fn cmp(a: &[Limb], b: &[Limb]) -> Ordering {
+    let (a, b) = (trim(a), trim(b));
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// Adds `b` to `a` in place, returns the carry out of `a`. `a` must be
+/// at least as long as `b`.
+pub fn add_assign(a: &mut [Limb], b: &[Limb]) -> bool {
+    let mut carry = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !carry {
+            break;
+        }
+        let (s, c1) = x.overflowing_add(y);
+        let (s, c2) = s.overflowing_add(carry as Limb);
+        *x = s;
+        carry = c1 || c2;
+    }
+    carry
+}
+
+/// Subtracts `b` from `a` in place, returns the borrow out of `a`. `a`
+/// must be at least as long as `b`.
+pub fn sub_assign(a: &mut [Limb], b: &[Limb]) -> bool {
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (d, b1) = x.overflowing_sub(y);
+        let (d, b2) = d.overflowing_sub(borrow as Limb);
+        *x = d;
+        borrow = b1 || b2;
+    }
+    borrow
+}
+
+pub fn add(a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(a.len() + 1);
+    sum.extend_from_slice(a);
+    if add_assign(&mut sum, b) {
+        sum.push(1);
+    }
+    sum
+}
+
+/// `a - b`, `a` must not be less than `b`.
+pub fn sub(a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+    let b = trim(b);
+    let mut diff = a.to_vec();
+    let borrow = sub_assign(&mut diff, b);
+    debug_assert!(!borrow);
+    normalize(&mut diff);
+    diff
+}
+
+/// Multiplies `a` by a limb and adds a limb in place.
+pub fn mul_add_small(a: &mut Vec<Limb>, m: Limb, add: Limb) {
+    let mut carry = add as DoubleLimb;
+    for x in a.iter_mut() {
+        let t = *x as DoubleLimb * m as DoubleLimb + carry;
+        *x = t as Limb;
+        carry = t >> LIMB_BITS;
+    }
+    if carry != 0 {
+        a.push(carry as Limb);
+    }
+}
+
+/// Divides `a` by a non-zero limb in place, returns the remainder.
+pub fn div_rem_small(a: &mut [Limb], d: Limb) -> Limb {
+    let mut rem: DoubleLimb = 0;
+    for x in a.iter_mut().rev() {
+        let cur = (rem << LIMB_BITS) | *x as DoubleLimb;
+        *x = (cur / d as DoubleLimb) as Limb;
+        rem = cur % d as DoubleLimb;
+    }
+    rem as Limb
+}
+
+/// `a << bits`, with one more limb for the bits shifted out at the top.
+pub fn shl(a: &[Limb], bits: usize) -> Vec<Limb> {
+    let limbs = bits / LIMB_BITS as usize;
+    let bits = (bits % LIMB_BITS as usize) as u32;
+    let mut r = vec![0; limbs];
+    r.reserve(a.len() + 1);
+    if bits == 0 {
+        r.extend_from_slice(a);
+        r.push(0);
+    } else {
+        let mut carry = 0;
+        for &x in a {
+            r.push((x << bits) | carry);
+            carry = x >> (LIMB_BITS - bits);
+        }
+        r.push(carry);
+    }
+    r
+}
+
+/// Shifts `a` right by less than a limb in place.
+pub fn shr_small(a: &mut [Limb], bits: u32) {
+    if bits == 0 {
+        return;
+    }
+    let mut carry = 0;
+    for x in a.iter_mut().rev() {
+        let next = *x << (LIMB_BITS - bits);
+        *x = (*x >> bits) | carry;
+        carry = next;
+    }
+}
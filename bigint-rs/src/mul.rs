@@ -0,0 +1,88 @@
+//! Multiplication: schoolbook for short numbers, Karatsuba for long ones.
+//!
+//! Karatsuba splits both numbers in halves, a = a1*B^h + a0 and
+//! b = b1*B^h + b0, and gets the product from three half-size products
+//! instead of four:
+//!
+//! a*b = z2*B^2h + z1*B^h + z0, where z0 = a0*b0, z2 = a1*b1 and
+//! z1 = (a0 + a1)*(b0 + b1) - z0 - z2
+//!
+//! which makes O(n^1.58) limb operations instead of O(n^2). The extra
+//! additions don't pay off for short numbers, so below a threshold the
+//! halves are multiplied with the schoolbook method.
+//!
+//! See https://en.wikipedia.org/wiki/Karatsuba_algorithm
+
+use crate::limbs::{self, DoubleLimb, Limb, LIMB_BITS};
+
+/// Length of the shorter number, in limbs, from which on Karatsuba is
+/// used.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Normalized product.
+pub fn mul(a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+    let (a, b) = (limbs::trim(a), limbs::trim(b));
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut r = if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        schoolbook(a, b)
+    } else {
+        karatsuba(a, b)
+    };
+    limbs::normalize(&mut r);
+    r
+}
+
+/// Product with `a.len() + b.len()` limbs, O(a.len() * b.len()).
+pub fn schoolbook(a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+    let mut r = vec![0; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0 {
+            continue;
+        }
+        let mut carry: DoubleLimb = 0;
+        for (j, &y) in b.iter().enumerate() {
+            // at most (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1
+            let t = x as DoubleLimb * y as DoubleLimb + r[i + j] as DoubleLimb + carry;
+            r[i + j] = t as Limb;
+            carry = t >> LIMB_BITS;
+        }
+        r[i + b.len()] = carry as Limb;
+    }
+    r
+}
+
+fn split(a: &[Limb], at: usize) -> (&[Limb], &[Limb]) {
+    if a.len() <= at {
+        (a, &[])
+    } else {
+        a.split_at(at)
+    }
+}
+
+/// Product with `a.len() + b.len() + 1` limbs.
+pub fn karatsuba(a: &[Limb], b: &[Limb]) -> Vec<Limb> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut r = vec![0; a.len() + b.len() + 1];
+    if a.len() >= 2 * b.len() {
+        // halves of `a` would still be longer than `b`, multiply
+        // `b`-sized chunks of `a` by `b` instead
+        for (i, chunk) in a.chunks(b.len()).enumerate() {
+            limbs::add_assign(&mut r[i * b.len()..], &mul(chunk, b));
+        }
+        return r;
+    }
+    let half = a.len() / 2;
+    let (a0, a1) = split(a, half);
+    let (b0, b1) = split(b, half);
+    let z0 = mul(a0, b0);
+    let z2 = mul(a1, b1);
+    let mut z1 = mul(&limbs::add(a0, a1), &limbs::add(b0, b1));
+    limbs::sub_assign(&mut z1, &z0);
+    limbs::sub_assign(&mut z1, &z2);
+    limbs::add_assign(&mut r, &z0);
+    limbs::add_assign(&mut r[half..], limbs::trim(&z1));
+    limbs::add_assign(&mut r[2 * half..], &z2);
+    r
+}
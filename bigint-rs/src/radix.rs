@@ -0,0 +1,112 @@
+//! Conversion from and to strings of digits.
+//!
+//! Digits are handled in chunks, as many as fit in a limb, so that each
+//! chunk takes one multiply-add when parsing and one division by a limb
+//! when formatting.
+
+use std::fmt;
+
+use crate::limbs::{self, Limb};
+
+/// Error parsing a big integer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError {
+    kind: ErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ErrorKind {
+    Empty,
+    InvalidDigit,
+}
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Empty => write!(f, "cannot parse integer from empty string"),
+            ErrorKind::InvalidDigit => write!(f, "invalid digit found in string"),
+        }
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+fn check_radix(radix: u32) {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix {} out of range 2..=36",
+        radix
+    );
+}
+
+/// Digits per chunk and radix^digits, the largest power fitting a limb.
+fn chunk(radix: u32) -> (usize, Limb) {
+    let mut digits = 1;
+    let mut base = radix;
+    while let Some(b) = base.checked_mul(radix) {
+        base = b;
+        digits += 1;
+    }
+    (digits, base)
+}
+
+/// Parses digits without sign into normalized limbs.
+pub fn parse(s: &str, radix: u32) -> Result<Vec<Limb>, ParseBigIntError> {
+    check_radix(radix);
+    if s.is_empty() {
+        return Err(ParseBigIntError {
+            kind: ErrorKind::Empty,
+        });
+    }
+    let (digits, base) = chunk(radix);
+    let mut v = Vec::new();
+    for chunk in s.as_bytes().chunks(digits) {
+        let mut x: Limb = 0;
+        for &b in chunk {
+            let d = (b as char).to_digit(radix).ok_or(ParseBigIntError {
+                kind: ErrorKind::InvalidDigit,
+            })?;
+            x = x * radix + d;
+        }
+        let m = if chunk.len() == digits {
+            base
+        } else {
+            radix.pow(chunk.len() as u32)
+        };
+        limbs::mul_add_small(&mut v, m, x);
+    }
+    limbs::normalize(&mut v);
+    Ok(v)
+}
+
+/// Formats limbs as digits without sign, lowercase.
+pub fn format(v: &[Limb], radix: u32) -> String {
+    check_radix(radix);
+    let mut q = limbs::trim(v).to_vec();
+    if q.is_empty() {
+        return "0".to_owned();
+    }
+    let (digits, base) = chunk(radix);
+    // least significant first
+    let mut chunks = Vec::new();
+    while !q.is_empty() {
+        chunks.push(limbs::div_rem_small(&mut q, base));
+        limbs::normalize(&mut q);
+    }
+    let mut s = String::with_capacity(chunks.len() * digits);
+    let mut buf = Vec::with_capacity(digits);
+    for (i, &c) in chunks.iter().rev().enumerate() {
+        buf.clear();
+        let mut c = c;
+        while c != 0 {
+            buf.push(std::char::from_digit(c % radix, radix).unwrap());
+            c /= radix;
+        }
+        // all chunks but the top one are padded with zeros
+        if i > 0 {
+            buf.resize(digits, '0');
+        }
+        s.extend(buf.iter().rev());
+    }
+    s
+}
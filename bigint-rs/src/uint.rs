@@ -0,0 +1,271 @@
+//! Unsigned big integer.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+use std::str::FromStr;
+
+use crate::limbs::{self, Limb, LIMB_BITS};
+use crate::{div, mul, radix, ParseBigIntError};
+
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Least significant first, no zero limbs at the top.
+    limbs: Vec<Limb>,
+}
+
+impl BigUint {
+    fn from_limbs(mut limbs: Vec<Limb>) -> Self {
+        limbs::normalize(&mut limbs);
+        Self { limbs }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self { limbs: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of bits without leading zeros, 0 for zero.
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * LIMB_BITS as usize - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Number of zero bits at the bottom, `None` for zero.
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let i = self.limbs.iter().position(|&x| x != 0)?;
+        Some(i * LIMB_BITS as usize + self.limbs[i].trailing_zeros() as usize)
+    }
+
+    /// Value as u128 if it fits.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.bits() > 128 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |acc, &x| (acc << LIMB_BITS) | x as u128),
+        )
+    }
+
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        radix::parse(s, radix).map(Self::from_limbs)
+    }
+
+    /// Digits in `radix`, lowercase, no prefix.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        radix::format(&self.limbs, radix)
+    }
+
+    /// Quotient and remainder. Panics if `other` is zero.
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = div::div_rem(&self.limbs, &other.limbs);
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    /// `self` to the power of `exp`, by repeated squaring.
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut acc = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = &acc * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        acc
+    }
+
+    /// Greatest common divisor, by binary (Stein's) gcd. The gcd of zero
+    /// and n is n.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (Some(za), Some(zb)) = (self.trailing_zeros(), other.trailing_zeros()) else {
+            return if self.is_zero() {
+                other.clone()
+            } else {
+                self.clone()
+            };
+        };
+        let mut a = self >> za;
+        let mut b = other >> zb;
+        loop {
+            // both odd
+            if a > b {
+                std::mem::swap(&mut a, &mut b);
+            }
+            limbs::sub_assign(&mut b.limbs, &a.limbs);
+            limbs::normalize(&mut b.limbs);
+            match b.trailing_zeros() {
+                Some(z) => b = &b >> z,
+                None => break,
+            }
+        }
+        a << za.min(zb)
+    }
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigUint {
+            fn from(mut x: $t) -> Self {
+                let mut limbs = Vec::new();
+                while x != 0 {
+                    limbs.push(x as Limb);
+                    // shifting by the full width of u32 would overflow
+                    x = x.checked_shr(LIMB_BITS).unwrap_or(0);
+                }
+                Self { limbs }
+            }
+        }
+    )*};
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128, usize);
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        limbs::cmp(&self.limbs, &other.limbs)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(limbs::add(&self.limbs, &other.limbs))
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if `other` is greater than `self`.
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(self >= other, "subtraction underflow");
+        BigUint::from_limbs(limbs::sub(&self.limbs, &other.limbs))
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul::mul(&self.limbs, &other.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+forward_binop!(BigUint, Add, add, AddAssign, add_assign);
+forward_binop!(BigUint, Sub, sub, SubAssign, sub_assign);
+forward_binop!(BigUint, Mul, mul, MulAssign, mul_assign);
+forward_binop!(BigUint, Div, div, DivAssign, div_assign);
+forward_binop!(BigUint, Rem, rem, RemAssign, rem_assign);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        BigUint::from_limbs(limbs::shl(&self.limbs, bits))
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        let skip = bits / LIMB_BITS as usize;
+        if skip >= self.limbs.len() {
+            return BigUint::zero();
+        }
+        let mut limbs = self.limbs[skip..].to_vec();
+        limbs::shr_small(&mut limbs, (bits % LIMB_BITS as usize) as u32);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    /// Parses decimal digits.
+    fn from_str(s: &str) -> Result<Self, ParseBigIntError> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+impl fmt::UpperHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16).to_uppercase())
+    }
+}
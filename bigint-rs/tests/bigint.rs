@@ -0,0 +1,283 @@
+//! Arithmetic checked against u128/i128 where the results fit, division
+//! checked by `q * v + r == u` on numbers on both sides of the Karatsuba
+//! threshold, and radix conversion round trips.
+
+use bigint::{BigInt, BigUint};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Random value of a random bit length up to `bits`.
+    fn bits(&mut self, bits: u32) -> u128 {
+        let x = (self.next() as u128) << 64 | self.next() as u128;
+        let n = (self.next() % (bits as u64 + 1)) as u32;
+        if n == 0 {
+            0
+        } else {
+            x >> (128 - n)
+        }
+    }
+
+    /// Random number of exactly `limbs` 32-bit limbs.
+    fn biguint(&mut self, limbs: usize) -> BigUint {
+        let mut s = String::new();
+        for i in 0..limbs {
+            let mut x = self.next() as u32;
+            if i == 0 && x == 0 {
+                x = 1;
+            }
+            s.push_str(&format!("{:08x}", x));
+        }
+        BigUint::from_str_radix(&s, 16).unwrap()
+    }
+}
+
+/// Digits of `x` in `radix`, for checking against the crate's.
+fn naive_radix(mut x: u128, radix: u32) -> String {
+    if x == 0 {
+        return "0".to_owned();
+    }
+    let mut digits = Vec::new();
+    while x != 0 {
+        digits.push(std::char::from_digit((x % radix as u128) as u32, radix).unwrap());
+        x /= radix as u128;
+    }
+    digits.iter().rev().collect()
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[test]
+fn unsigned_ops_as_u128() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..20_000 {
+        let (a, b) = (rng.bits(128), rng.bits(128));
+        let (x, y) = (BigUint::from(a), BigUint::from(b));
+        assert_eq!(x.to_u128(), Some(a));
+        assert_eq!(x.bits(), 128 - a.leading_zeros() as usize);
+        assert_eq!(
+            x.trailing_zeros(),
+            (a != 0).then_some(a.trailing_zeros() as usize)
+        );
+        assert_eq!(x.is_zero(), a == 0);
+        assert_eq!(x.to_string(), a.to_string());
+        assert_eq!(format!("{:x}", x), format!("{:x}", a));
+        assert_eq!(format!("{:X}", x), format!("{:X}", a));
+        assert_eq!(x.cmp(&y), a.cmp(&b));
+        match a.checked_add(b) {
+            Some(s) => assert_eq!((&x + &y).to_u128(), Some(s)),
+            None => assert_eq!((&x + &y).bits(), 129),
+        }
+        let (hi, lo) = (a.max(b), a.min(b));
+        assert_eq!(
+            (BigUint::from(hi) - BigUint::from(lo)).to_u128(),
+            Some(hi - lo)
+        );
+        let (a64, b64) = (a >> 64, b >> 64);
+        assert_eq!(
+            (BigUint::from(a64) * BigUint::from(b64)).to_u128(),
+            Some(a64 * b64)
+        );
+        if let (Some(qq), Some(rr)) = (a.checked_div(b), a.checked_rem(b)) {
+            let (q, r) = x.div_rem(&y);
+            assert_eq!(q.to_u128(), Some(qq));
+            assert_eq!(r.to_u128(), Some(rr));
+            assert_eq!((&x / &y).to_u128(), Some(qq));
+            assert_eq!((&x % &y).to_u128(), Some(rr));
+        }
+        let s = (rng.next() % 128) as usize;
+        assert_eq!((&x >> s).to_u128(), Some(a >> s));
+        assert_eq!((BigUint::from(a >> s) << s).to_u128(), Some(a >> s << s));
+        assert_eq!(x.gcd(&y).to_u128(), Some(gcd(a, b)));
+        let base = rng.bits(16);
+        let exp = (rng.next() % 8) as u32;
+        assert_eq!(BigUint::from(base).pow(exp).to_u128(), Some(base.pow(exp)));
+    }
+}
+
+#[test]
+fn signed_ops_as_i128() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..20_000 {
+        let a = rng.bits(63) as i64 as i128 * if rng.next() & 1 == 0 { 1 } else { -1 };
+        let b = rng.bits(63) as i64 as i128 * if rng.next() & 1 == 0 { 1 } else { -1 };
+        let (x, y) = (BigInt::from(a), BigInt::from(b));
+        assert_eq!(x.to_i128(), Some(a));
+        assert_eq!(x.is_negative(), a < 0);
+        assert_eq!(x.to_string(), a.to_string());
+        assert_eq!(x.cmp(&y), a.cmp(&b));
+        assert_eq!((-&x).to_i128(), Some(-a));
+        assert_eq!(x.abs().to_i128(), Some(a.abs()));
+        assert_eq!((&x + &y).to_i128(), Some(a + b));
+        assert_eq!((&x - &y).to_i128(), Some(a - b));
+        assert_eq!((&x * &y).to_i128(), Some(a * b));
+        if let (Some(qq), Some(rr)) = (a.checked_div(b), a.checked_rem(b)) {
+            let (q, r) = x.div_rem(&y);
+            assert_eq!(q.to_i128(), Some(qq));
+            assert_eq!(r.to_i128(), Some(rr));
+            assert_eq!((&x / &y).to_i128(), Some(qq));
+            assert_eq!((&x % &y).to_i128(), Some(rr));
+        }
+        assert_eq!(
+            x.gcd(&y).to_i128(),
+            Some(gcd(a.unsigned_abs(), b.unsigned_abs()) as i128)
+        );
+        let exp = (rng.next() % 3) as u32;
+        assert_eq!(
+            BigInt::from(a >> 40).pow(exp).to_i128(),
+            Some((a >> 40).pow(exp))
+        );
+    }
+    for a in [i128::MIN, i128::MAX, 0, -1] {
+        assert_eq!(BigInt::from(a).to_i128(), Some(a));
+        assert_eq!(BigInt::from(a).to_string(), a.to_string());
+    }
+    assert_eq!((BigInt::from(i128::MIN) - BigInt::one()).to_i128(), None);
+    assert_eq!((BigInt::from(i128::MAX) + BigInt::one()).to_i128(), None);
+}
+
+#[test]
+fn truncating_division_signs() {
+    for (a, b, q, r) in [
+        (7, 2, 3, 1),
+        (7, -2, -3, 1),
+        (-7, 2, -3, -1),
+        (-7, -2, 3, -1),
+        (-6, 2, -3, 0),
+        (1, -7, 0, 1),
+        (-1, 7, 0, -1),
+    ] {
+        let (bq, br) = BigInt::from(a).div_rem(&BigInt::from(b));
+        assert_eq!(
+            (bq.to_i128(), br.to_i128()),
+            (Some(q), Some(r)),
+            "{a} / {b}"
+        );
+        // no negative zero
+        assert_eq!(br.is_negative(), r < 0);
+        assert_eq!(bq.is_negative(), q < 0);
+    }
+}
+
+#[test]
+fn division_identity_around_karatsuba() {
+    let mut rng = Rng(0xdeadbeefcafef00d);
+    // the threshold is 32 limbs, products of these cross it both ways
+    let sizes = [1, 2, 3, 16, 31, 32, 33, 47, 63, 64, 65, 80];
+    for &n in &sizes {
+        for &m in &sizes {
+            let (a, b) = (rng.biguint(n), rng.biguint(m));
+            let p = &a * &b;
+            assert!(p.bits() <= a.bits() + b.bits());
+            assert!(p.bits() + 1 >= a.bits() + b.bits());
+            // exact division undoes the product
+            let (q, r) = p.div_rem(&b);
+            assert_eq!(q, a);
+            assert!(r.is_zero());
+            // and a product plus a smaller remainder comes back apart
+            let c = &rng.biguint(m) % &b;
+            let (q, r) = (&p + &c).div_rem(&b);
+            assert_eq!(q, a);
+            assert_eq!(r, c);
+            // random dividend, divisor of any size
+            let u = rng.biguint(n + m);
+            let v = rng.biguint(m);
+            let (q, r) = u.div_rem(&v);
+            assert!(r < v);
+            assert_eq!(&q * &v + &r, u);
+        }
+    }
+}
+
+#[test]
+fn product_against_shifts() {
+    // (2^k - 1)^2 == 2^2k - 2^(k+1) + 1, on both sides of the threshold
+    let one = BigUint::one();
+    for k in [1, 31, 32, 33, 1000, 1024, 32 * 32, 32 * 33, 5000] {
+        let x = (&one << k) - &one;
+        let want = (&one << (2 * k)) - (&one << (k + 1)) + &one;
+        assert_eq!(&x * &x, want, "k = {k}");
+        assert_eq!(x.pow(2), want);
+    }
+}
+
+#[test]
+fn radix_round_trips() {
+    let mut rng = Rng(0x0123456789abcdef);
+    for radix in 2..=36 {
+        for _ in 0..200 {
+            let a = rng.bits(128);
+            let s = BigUint::from(a).to_str_radix(radix);
+            assert_eq!(s, naive_radix(a, radix));
+            assert_eq!(
+                BigUint::from_str_radix(&s, radix).unwrap().to_u128(),
+                Some(a)
+            );
+            assert_eq!(
+                BigUint::from_str_radix(&s.to_uppercase(), radix)
+                    .unwrap()
+                    .to_u128(),
+                Some(a)
+            );
+            let neg = -BigInt::from(a);
+            let s = neg.to_str_radix(radix);
+            assert_eq!(BigInt::from_str_radix(&s, radix).unwrap(), neg);
+        }
+        for limbs in [1, 5, 40, 100] {
+            let x = rng.biguint(limbs);
+            let s = x.to_str_radix(radix);
+            assert_eq!(BigUint::from_str_radix(&s, radix).unwrap(), x);
+            let plus = format!("+{}", s);
+            assert_eq!(
+                BigInt::from_str_radix(&plus, radix).unwrap(),
+                BigInt::from(x)
+            );
+        }
+        assert_eq!(BigUint::zero().to_str_radix(radix), "0");
+        assert!(BigInt::from_str_radix("-0", radix).unwrap().is_zero());
+    }
+    // leading zeros are fine
+    assert_eq!("000123".parse::<BigUint>().unwrap().to_u128(), Some(123));
+}
+
+#[test]
+fn parse_errors() {
+    let empty = "cannot parse integer from empty string";
+    let invalid = "invalid digit found in string";
+    assert_eq!("".parse::<BigUint>().unwrap_err().to_string(), empty);
+    for s in ["", "-", "+"] {
+        assert_eq!(s.parse::<BigInt>().unwrap_err().to_string(), empty, "{s:?}");
+    }
+    for s in ["+1", "-1", " 1", "1 ", "1_000", "12a", "--1", "+-1", "1.0"] {
+        assert_eq!(
+            s.parse::<BigUint>().unwrap_err().to_string(),
+            invalid,
+            "{s:?}"
+        );
+    }
+    for s in ["--1", "+-1", "-+1", "- 1", "12a", "١"] {
+        assert_eq!(
+            s.parse::<BigInt>().unwrap_err().to_string(),
+            invalid,
+            "{s:?}"
+        );
+    }
+    assert!(BigUint::from_str_radix("2", 2).is_err());
+    assert!(BigUint::from_str_radix("z", 35).is_err());
+    assert_eq!(
+        BigUint::from_str_radix("z", 36).unwrap().to_u128(),
+        Some(35)
+    );
+}
@@ -0,0 +1,225 @@
+//! Growable vector of bits packed into u64 words.
+//!
+//! Bits past the length in the last word are always kept zero, so whole
+//! words can be counted and compared without masking.
+//!
+//! [`RankSelect`] indexes a bit vector in o(n) extra bits for constant time
+//! rank and O(log log n) select.
+
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+mod rank_select;
+
+pub use rank_select::RankSelect;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a vector of `len` bits all equal to `value`.
+    pub fn with_len(len: usize, value: bool) -> Self {
+        let word = if value { !0 } else { 0 };
+        let mut bv = Self {
+            words: vec![word; len.div_ceil(WORD_BITS)],
+            len,
+        };
+        bv.clear_tail();
+        bv
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Zeroes bits past the length.
+    fn clear_tail(&mut self) {
+        let rem = self.len % WORD_BITS;
+        if rem != 0 {
+            *self.words.last_mut().unwrap() &= (1 << rem) - 1;
+        }
+    }
+
+    pub fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    pub fn pop(&mut self) -> Option<bool> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.get(self.len - 1);
+        self.set(self.len - 1, false);
+        self.len -= 1;
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.pop();
+        }
+        Some(value)
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "index {} out of range for length {}", i, self.len);
+        self.words[i / WORD_BITS] >> (i % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "index {} out of range for length {}", i, self.len);
+        let word = &mut self.words[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Sets all bits to `value`.
+    pub fn fill(&mut self, value: bool) {
+        let word = if value { !0 } else { 0 };
+        self.words.fill(word);
+        self.clear_tail();
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Number of set bits at positions less than `i`, i.e. rank of `i`.
+    /// `i` may be the length.
+    pub fn count_ones_before(&self, i: usize) -> usize {
+        assert!(i <= self.len, "index {} out of range for length {}", i, self.len);
+        let (q, r) = (i / WORD_BITS, i % WORD_BITS);
+        let full: usize = self.words[..q].iter().map(|w| w.count_ones() as usize).sum();
+        if r == 0 {
+            full
+        } else {
+            full + (self.words[q] & ((1 << r) - 1)).count_ones() as usize
+        }
+    }
+
+    /// Bits in order.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+
+    /// Positions of set bits in increasing order.
+    pub fn ones(&self) -> Ones<'_> {
+        Ones {
+            words: &self.words,
+            base: 0,
+            word: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    pub(crate) fn words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+pub struct Ones<'a> {
+    words: &'a [u64],
+    /// Position of the first bit of `word`.
+    base: usize,
+    /// Bits of the current word not returned yet.
+    word: u64,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.words = self.words.get(1..)?;
+            self.word = *self.words.first()?;
+            self.base += WORD_BITS;
+        }
+        let i = self.base + self.word.trailing_zeros() as usize;
+        // clear lowest set bit
+        self.word &= self.word - 1;
+        Some(i)
+    }
+}
+
+macro_rules! impl_bitop {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident) => {
+        /// Vectors must be of the same length.
+        impl $op_assign<&BitVec> for BitVec {
+            fn $f_assign(&mut self, other: &BitVec) {
+                assert_eq!(self.len, other.len, "bit vectors of different lengths");
+                for (a, b) in self.words.iter_mut().zip(&other.words) {
+                    a.$f_assign(b);
+                }
+            }
+        }
+
+        impl $op for &BitVec {
+            type Output = BitVec;
+
+            fn $f(self, other: &BitVec) -> BitVec {
+                let mut bv = self.clone();
+                bv.$f_assign(other);
+                bv
+            }
+        }
+    };
+}
+
+impl_bitop!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bitop!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_bitop!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for &BitVec {
+    type Output = BitVec;
+
+    fn not(self) -> BitVec {
+        let mut bv = BitVec {
+            words: self.words.iter().map(|w| !w).collect(),
+            len: self.len,
+        };
+        bv.clear_tail();
+        bv
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bv = Self::new();
+        bv.extend(iter);
+        bv
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+/// Bits as a string of 0s and 1s, first bit first.
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for value in self.iter() {
+            f.write_str(if value { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
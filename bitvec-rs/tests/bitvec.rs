@@ -0,0 +1,115 @@
+//! `BitVec` against `Vec<bool>` under random pushes, pops and sets, with
+//! lengths around word boundaries.
+
+use bitvec::BitVec;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn check(bv: &BitVec, model: &[bool]) {
+    assert_eq!(bv.len(), model.len());
+    assert_eq!(bv.is_empty(), model.is_empty());
+    assert!(bv.iter().eq(model.iter().copied()));
+    assert_eq!(bv.count_ones(), model.iter().filter(|&&b| b).count());
+    let ones: Vec<usize> = (0..model.len()).filter(|&i| model[i]).collect();
+    assert_eq!(bv.ones().collect::<Vec<_>>(), ones);
+    let mut before = 0;
+    for (i, &b) in model.iter().enumerate() {
+        assert_eq!(bv.get(i), b);
+        assert_eq!(bv.count_ones_before(i), before);
+        before += b as usize;
+    }
+    assert_eq!(bv.count_ones_before(model.len()), before);
+}
+
+#[test]
+fn random_edits() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let mut bv = BitVec::new();
+    let mut model = Vec::new();
+    for step in 0..20_000 {
+        match rng.below(8) {
+            0..=3 => {
+                let b = rng.below(2) == 0;
+                bv.push(b);
+                model.push(b);
+            }
+            4..=5 => assert_eq!(bv.pop(), model.pop()),
+            _ if !model.is_empty() => {
+                let i = rng.below(model.len() as u64) as usize;
+                let b = rng.below(2) == 0;
+                bv.set(i, b);
+                model[i] = b;
+            }
+            _ => {}
+        }
+        if step % 500 == 0 {
+            check(&bv, &model);
+        }
+    }
+    check(&bv, &model);
+    // popping cleared bits past the length, so equal vectors compare equal
+    let rebuilt: BitVec = model.iter().copied().collect();
+    assert_eq!(bv, rebuilt);
+}
+
+#[test]
+fn fill_and_with_len() {
+    for len in [0, 1, 63, 64, 65, 127, 128, 129, 1000] {
+        for value in [false, true] {
+            let bv = BitVec::with_len(len, value);
+            check(&bv, &vec![value; len]);
+            let mut other = BitVec::with_len(len, !value);
+            other.fill(value);
+            assert_eq!(other, bv);
+            let pushed: BitVec = std::iter::repeat_n(value, len).collect();
+            assert_eq!(pushed, bv);
+        }
+    }
+    assert_eq!(BitVec::new().pop(), None);
+}
+
+#[test]
+fn bit_operations() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for len in [0, 1, 63, 64, 65, 200] {
+        let a: Vec<bool> = (0..len).map(|_| rng.below(2) == 0).collect();
+        let b: Vec<bool> = (0..len).map(|_| rng.below(2) == 0).collect();
+        let (x, y): (BitVec, BitVec) = (a.iter().copied().collect(), b.iter().copied().collect());
+        let zip = |f: fn(bool, bool) -> bool| -> Vec<bool> {
+            a.iter().zip(&b).map(|(&p, &q)| f(p, q)).collect()
+        };
+        check(&(&x & &y), &zip(|p, q| p & q));
+        check(&(&x | &y), &zip(|p, q| p | q));
+        check(&(&x ^ &y), &zip(|p, q| p ^ q));
+        // not keeps bits past the length zero
+        let not: Vec<bool> = a.iter().map(|&p| !p).collect();
+        check(&!&x, &not);
+        assert_eq!(!&!&x, x);
+        let mut z = x.clone();
+        z ^= &x;
+        assert_eq!(z, BitVec::with_len(len, false));
+    }
+    assert_eq!(
+        format!("{:?}", [true, false, true].into_iter().collect::<BitVec>()),
+        "101"
+    );
+}
+
+#[test]
+#[should_panic(expected = "different lengths")]
+fn bit_operation_lengths_differ() {
+    let _ = &BitVec::with_len(3, true) & &BitVec::with_len(4, true);
+}
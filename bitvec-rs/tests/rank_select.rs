@@ -0,0 +1,121 @@
+//! Rank and select against a linear scan, at every position of vectors
+//! that are empty, all zeros, all ones, random of various densities, and
+//! of lengths around word and superblock boundaries.
+
+use bitvec::{BitVec, RankSelect};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Lengths around words of 64 bits, and superblocks, which are 64 or
+/// 256 bits at these lengths.
+const LENGTHS: &[usize] = &[
+    0, 1, 63, 64, 65, 511, 512, 513, 1023, 1024, 1025, 4096, 5000,
+];
+
+fn check(bits: &[bool]) {
+    let bv: BitVec = bits.iter().copied().collect();
+    let rs = RankSelect::new(bv);
+    assert_eq!(rs.len(), bits.len());
+    assert_eq!(rs.is_empty(), bits.is_empty());
+    let mut ones = 0;
+    for (i, &b) in bits.iter().enumerate() {
+        assert_eq!(rs.rank1(i), ones, "rank1({})", i);
+        assert_eq!(rs.rank0(i), i - ones);
+        if b {
+            assert_eq!(rs.select1(ones), Some(i), "select1({})", ones);
+            ones += 1;
+        }
+    }
+    assert_eq!(rs.rank1(bits.len()), ones);
+    assert_eq!(rs.rank0(bits.len()), bits.len() - ones);
+    assert_eq!(rs.count_ones(), ones);
+    assert_eq!(rs.select1(ones), None);
+    assert_eq!(rs.select1(usize::MAX), None);
+}
+
+#[test]
+fn empty_zeros_and_ones() {
+    for &len in LENGTHS {
+        check(&vec![false; len]);
+        check(&vec![true; len]);
+    }
+}
+
+#[test]
+fn random_densities() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for &len in LENGTHS.iter().chain(&[100_000]) {
+        // from very sparse to very dense, so select samples are both
+        // many superblocks and a few bits apart
+        for per_1000 in [1, 10, 100, 500, 900, 999] {
+            let bits: Vec<bool> = (0..len).map(|_| rng.below(1000) < per_1000).collect();
+            check(&bits);
+        }
+    }
+}
+
+#[test]
+fn ones_only_at_boundaries() {
+    for &len in LENGTHS {
+        let bits: Vec<bool> = (0..len)
+            .map(|i| i % 64 == 0 || i % 64 == 63 || i % 512 == 511)
+            .collect();
+        check(&bits);
+        // one set bit every few superblocks
+        let bits: Vec<bool> = (0..len * 4).map(|i| i % 1500 == 1499).collect();
+        check(&bits);
+    }
+}
+
+#[test]
+fn exactly_sample_sized_runs() {
+    // runs of 512 ones, a multiple of the 256 set bits per sample, so
+    // samples fall right at superblock starts
+    for gap in [0, 1, 511, 512, 513] {
+        let bits: Vec<bool> = (0..4)
+            .flat_map(|_| std::iter::repeat_n(true, 512).chain(std::iter::repeat_n(false, gap)))
+            .collect();
+        check(&bits);
+    }
+}
+
+#[test]
+fn sparse_select_groups() {
+    // at 2^21 bits blocks are 32 bits, so groups of 1024 set bits
+    // spread over more than 2^20 bits are stored position by position
+    let len = 1 << 21;
+    for gap in [1500, 3000, 100_000] {
+        let bits: Vec<bool> = (0..len).map(|i| i % gap == 7).collect();
+        check(&bits);
+    }
+    // dense then sparse, and the other way round
+    let dense = |i: usize| i.is_multiple_of(3);
+    let sparse = |i: usize| i.is_multiple_of(1200);
+    let bits: Vec<bool> = (0..len)
+        .map(|i| if i < len / 4 { dense(i) } else { sparse(i) })
+        .collect();
+    check(&bits);
+    let bits: Vec<bool> = (0..len)
+        .map(|i| if i < len / 4 * 3 { sparse(i) } else { dense(i) })
+        .collect();
+    check(&bits);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn rank_past_end() {
+    RankSelect::new(BitVec::with_len(10, true)).rank1(11);
+}
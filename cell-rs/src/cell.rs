@@ -0,0 +1,107 @@
+//! Mutable memory location for values that are moved in and out whole.
+//!
+//! `Cell` never hands out references to its value, only copies of it or
+//! the value itself in exchange for another one. With no references
+//! there is nothing to keep track of, so the cell needs no borrow flag
+//! and its methods can't fail.
+//!
+//! Two threads could still both write the value at once, so the cell is
+//! not `Sync`:
+//!
+//! ```compile_fail
+//! use cell::Cell;
+//! use std::thread;
+//!
+//! let c = Cell::new(1);
+//! thread::scope(|s| {
+//!     s.spawn(|| c.set(2));
+//!     s.spawn(|| c.set(3));
+//! });
+//! ```
+
+use std::cell::UnsafeCell;
+use std::fmt;
+
+pub struct Cell<T: ?Sized> {
+    value: UnsafeCell<T>,
+}
+
+impl<T> Cell<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        // old value is dropped here, after the cell has the new one, so
+        // a drop that looks into the cell sees a valid value
+        drop(self.replace(value));
+    }
+
+    pub fn replace(&self, value: T) -> T {
+        // SAFETY: no references to the value exist outside this call and
+        // the cell is not shared between threads
+        unsafe { std::mem::replace(&mut *self.value.get(), value) }
+    }
+
+    pub fn swap(&self, other: &Self) {
+        if std::ptr::eq(self, other) {
+            return;
+        }
+        // SAFETY: as in `replace`, and the cells are different so the
+        // references don't overlap
+        unsafe { std::ptr::swap(self.value.get(), other.value.get()) }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: Copy> Cell<T> {
+    pub fn get(&self) -> T {
+        // SAFETY: value is copied out, no reference to it escapes
+        unsafe { *self.value.get() }
+    }
+
+    /// Replaces the value with `f` of it, returns the new value. Values
+    /// that aren't `Copy` can be updated with `take` and `set`.
+    pub fn update(&self, f: impl FnOnce(T) -> T) -> T {
+        let new = f(self.get());
+        self.set(new);
+        new
+    }
+}
+
+impl<T: Default> Cell<T> {
+    /// Takes the value, leaving the default in its place.
+    pub fn take(&self) -> T {
+        self.replace(T::default())
+    }
+}
+
+impl<T: ?Sized> Cell<T> {
+    /// Mutable reference to the cell proves nobody else can access it.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for Cell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy> Clone for Cell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for Cell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Cell").field("value", &self.get()).finish()
+    }
+}
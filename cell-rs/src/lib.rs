@@ -0,0 +1,17 @@
+//! Shareable mutable containers for single-threaded code.
+//!
+//! Rust normally allows either many shared references or one mutable
+//! reference to a value, checked at compile time. The types here move
+//! part of that check to run time, so a value behind a shared reference
+//! can still be changed. They are all built on `UnsafeCell`, the only
+//! legal way to mutate through a shared reference.
+//!
+//! See https://doc.rust-lang.org/std/cell/index.html
+
+mod cell;
+mod once;
+mod refcell;
+
+pub use cell::Cell;
+pub use once::OnceCell;
+pub use refcell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut};
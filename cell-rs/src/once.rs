@@ -0,0 +1,102 @@
+//! Cell that can be written only once.
+//!
+//! Once set, the value never changes, so shared references to it can be
+//! handed out without tracking them. Setting it again fails instead.
+//!
+//! Initialization checks and writes the state without synchronization,
+//! so two threads could initialize the cell at once and one of them
+//! would overwrite a value the other is already reading. Hence it's not
+//! `Sync`:
+//!
+//! ```compile_fail
+//! use cell::OnceCell;
+//! use std::thread;
+//!
+//! let c = OnceCell::new();
+//! thread::scope(|s| {
+//!     s.spawn(|| c.get_or_init(|| 1));
+//!     s.spawn(|| c.get_or_init(|| 2));
+//! });
+//! ```
+
+use std::cell::UnsafeCell;
+use std::fmt;
+
+pub struct OnceCell<T> {
+    value: UnsafeCell<Option<T>>,
+}
+
+impl<T> OnceCell<T> {
+    pub fn new() -> Self {
+        Self {
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        // SAFETY: value is only written while it's `None`, when there are
+        // no references to it
+        unsafe { (*self.value.get()).as_ref() }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.value.get_mut().as_mut()
+    }
+
+    /// Sets the value if the cell is empty, otherwise gives `value` back.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        if self.get().is_some() {
+            return Err(value);
+        }
+        // SAFETY: cell is empty, so there are no references to its value
+        unsafe { *self.value.get() = Some(value) };
+        Ok(())
+    }
+
+    /// Value of the cell, initialized with `f` if the cell is empty.
+    ///
+    /// Panics if `f` initializes the cell itself: the value returned by
+    /// `f` would have to overwrite the one `f` set, which may already be
+    /// referenced.
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = f();
+        assert!(self.set(value).is_ok(), "reentrant init");
+        self.get().unwrap()
+    }
+
+    pub fn into_inner(self) -> Option<T> {
+        self.value.into_inner()
+    }
+
+    /// Takes the value, leaving the cell empty. Mutable reference proves
+    /// nobody holds a reference to the value.
+    pub fn take(&mut self) -> Option<T> {
+        self.value.get_mut().take()
+    }
+}
+
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for OnceCell<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: UnsafeCell::new(self.get().cloned()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("OnceCell").field(value).finish(),
+            None => f.write_str("OnceCell(<uninit>)"),
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Mutable memory location with borrow rules checked at run time.
+//!
+//! The cell counts its live borrows in a flag: a positive value is the
+//! number of shared borrows, -1 means there is one mutable borrow and 0
+//! means no borrows. Borrowing hands out a guard that updates the flag
+//! and restores it when dropped. Breaking the borrow rules panics, or
+//! returns an error from the `try_` methods.
+//!
+//! The flag is changed without synchronization, so the cell must not be
+//! shared between threads, i.e. it's not `Sync`:
+//!
+//! ```compile_fail
+//! use cell::RefCell;
+//! use std::thread;
+//!
+//! let c = RefCell::new(1);
+//! thread::scope(|s| {
+//!     s.spawn(|| *c.borrow_mut() += 1);
+//!     s.spawn(|| *c.borrow_mut() += 1);
+//! });
+//! ```
+//!
+//! Borrow guards can't outlive the cell:
+//!
+//! ```compile_fail
+//! use cell::RefCell;
+//!
+//! let r = {
+//!     let c = RefCell::new(1);
+//!     c.borrow()
+//! };
+//! ```
+//!
+//! and a reference obtained from a guard can't outlive the guard, or the
+//! flag would say there are no borrows while the reference is still live:
+//!
+//! ```compile_fail
+//! use cell::RefCell;
+//!
+//! let c = RefCell::new(vec![1]);
+//! let first;
+//! {
+//!     let r = c.borrow();
+//!     first = &r[0];
+//! }
+//! c.borrow_mut().clear();
+//! println!("{}", first);
+//! ```
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use crate::Cell;
+
+type BorrowFlag = isize;
+
+const UNUSED: BorrowFlag = 0;
+const WRITING: BorrowFlag = -1;
+
+/// Cell is mutably borrowed, so it can't be borrowed again.
+#[derive(Debug)]
+pub struct BorrowError;
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "already mutably borrowed")
+    }
+}
+
+impl std::error::Error for BorrowError {}
+
+/// Cell is borrowed, so it can't be borrowed mutably.
+#[derive(Debug)]
+pub struct BorrowMutError;
+
+impl fmt::Display for BorrowMutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "already borrowed")
+    }
+}
+
+impl std::error::Error for BorrowMutError {}
+
+pub struct RefCell<T: ?Sized> {
+    borrow: Cell<BorrowFlag>,
+    value: UnsafeCell<T>,
+}
+
+impl<T> RefCell<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            borrow: Cell::new(UNUSED),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consuming the cell proves there are no borrows.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Replaces the value, returning the old one. Panics if borrowed.
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.borrow_mut(), value)
+    }
+
+    /// Swaps values of two cells. Panics if either is borrowed.
+    pub fn swap(&self, other: &Self) {
+        std::mem::swap(&mut *self.borrow_mut(), &mut *other.borrow_mut());
+    }
+}
+
+impl<T: ?Sized> RefCell<T> {
+    /// Borrows the value. Panics if it's mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        match self.try_borrow() {
+            Ok(r) => r,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_borrow(&self) -> Result<Ref<'_, T>, BorrowError> {
+        let b = self.borrow.get();
+        if b < UNUSED {
+            return Err(BorrowError);
+        }
+        assert!(b < BorrowFlag::MAX, "too many borrows");
+        self.borrow.set(b + 1);
+        Ok(Ref {
+            // SAFETY: pointer from `UnsafeCell::get` is never null
+            value: unsafe { NonNull::new_unchecked(self.value.get()) },
+            borrow: &self.borrow,
+            marker: PhantomData,
+        })
+    }
+
+    /// Borrows the value mutably. Panics if it's borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        match self.try_borrow_mut() {
+            Ok(r) => r,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_borrow_mut(&self) -> Result<RefMut<'_, T>, BorrowMutError> {
+        if self.borrow.get() != UNUSED {
+            return Err(BorrowMutError);
+        }
+        self.borrow.set(WRITING);
+        Ok(RefMut {
+            // SAFETY: pointer from `UnsafeCell::get` is never null
+            value: unsafe { NonNull::new_unchecked(self.value.get()) },
+            borrow: &self.borrow,
+            marker: PhantomData,
+        })
+    }
+
+    /// Mutable reference to the cell proves there are no borrows, so no
+    /// checks are needed.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for RefCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone> Clone for RefCell<T> {
+    /// Panics if the value is mutably borrowed.
+    fn clone(&self) -> Self {
+        Self::new(self.borrow().clone())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_borrow() {
+            Ok(r) => f.debug_struct("RefCell").field("value", &&*r).finish(),
+            Err(_) => f.debug_struct("RefCell").field("value", &"<borrowed>").finish(),
+        }
+    }
+}
+
+/// Shared borrow of a `RefCell`.
+///
+/// The value is held by pointer, not by reference: a reference field
+/// would claim the value stays borrowed for all of `'a`, also after
+/// `drop` has released the flag and the cell can be borrowed mutably.
+pub struct Ref<'a, T: ?Sized> {
+    value: NonNull<T>,
+    borrow: &'a Cell<BorrowFlag>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: ?Sized> Ref<'a, T> {
+    /// Another shared borrow of the same value. Not a method so it
+    /// doesn't shadow `clone` of `T`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn clone(r: &Self) -> Self {
+        let b = r.borrow.get();
+        assert!(b < BorrowFlag::MAX, "too many borrows");
+        r.borrow.set(b + 1);
+        Ref {
+            value: r.value,
+            borrow: r.borrow,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the flag counts this borrow, so there are no mutable ones
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: ?Sized> Drop for Ref<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.set(self.borrow.get() - 1);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Mutable borrow of a `RefCell`. Holds the value by pointer for the
+/// same reason as `Ref`, and is invariant in `T` like `&'a mut T`.
+pub struct RefMut<'a, T: ?Sized> {
+    value: NonNull<T>,
+    borrow: &'a Cell<BorrowFlag>,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<T: ?Sized> Deref for RefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the flag marks this borrow as the only one
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the flag marks this borrow as the only one
+        unsafe { self.value.as_mut() }
+    }
+}
+
+impl<T: ?Sized> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.set(UNUSED);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
@@ -0,0 +1,55 @@
+//! `Cell` and `OnceCell` give out values or references that stay valid.
+
+use std::rc::Rc;
+
+use cell::{Cell, OnceCell};
+
+#[test]
+fn cell_copy() {
+    let c = Cell::new(1);
+    c.set(2);
+    assert_eq!(c.get(), 2);
+    assert_eq!(c.update(|x| x * 10), 20);
+    assert_eq!(c.replace(3), 20);
+    let d = Cell::new(4);
+    c.swap(&d);
+    assert_eq!((c.get(), d.get()), (4, 3));
+}
+
+#[test]
+fn cell_non_copy() {
+    let c = Cell::new(vec![1]);
+    let mut v = c.take();
+    v.push(2);
+    c.set(v);
+    assert_eq!(c.replace(Vec::new()), [1, 2]);
+    c.swap(&c);
+    assert_eq!(c.into_inner(), []);
+}
+
+#[test]
+fn cell_drops_old_value() {
+    let rc = Rc::new(());
+    let c = Cell::new(rc.clone());
+    assert_eq!(Rc::strong_count(&rc), 2);
+    c.set(Rc::new(()));
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn once_cell() {
+    let c = OnceCell::new();
+    assert_eq!(c.get(), None);
+    let a = c.get_or_init(|| 1);
+    assert_eq!(c.set(2), Err(2));
+    assert_eq!(c.get_or_init(|| 3), &1);
+    assert_eq!(a, &1);
+    assert_eq!(c.into_inner(), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "reentrant init")]
+fn once_cell_reentrant_init() {
+    let c = OnceCell::new();
+    c.get_or_init(|| *c.get_or_init(|| 1) + 1);
+}
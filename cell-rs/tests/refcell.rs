@@ -0,0 +1,71 @@
+//! Borrow rules of `RefCell` are enforced at run time.
+
+use cell::{Ref, RefCell};
+
+#[test]
+fn shared_borrows() {
+    let c = RefCell::new(vec![1, 2]);
+    let a = c.borrow();
+    let b = c.borrow();
+    let d = Ref::clone(&a);
+    assert_eq!(a.len() + b.len() + d.len(), 6);
+    assert!(c.try_borrow_mut().is_err());
+}
+
+#[test]
+fn borrows_end_with_guards() {
+    let c = RefCell::new(1);
+    {
+        let _a = c.borrow();
+        let _b = c.borrow();
+        assert!(c.try_borrow_mut().is_err());
+    }
+    *c.borrow_mut() += 1;
+    {
+        let _m = c.borrow_mut();
+        assert!(c.try_borrow().is_err());
+        assert!(c.try_borrow_mut().is_err());
+    }
+    assert_eq!(*c.borrow(), 2);
+    assert_eq!(c.replace(5), 2);
+    assert_eq!(c.into_inner(), 5);
+}
+
+#[test]
+fn debug_while_borrowed() {
+    let c = RefCell::new(1);
+    assert_eq!(format!("{:?}", c), "RefCell { value: 1 }");
+    let _m = c.borrow_mut();
+    assert_eq!(format!("{:?}", c), "RefCell { value: \"<borrowed>\" }");
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn borrow_mut_while_borrowed() {
+    let c = RefCell::new(1);
+    let _a = c.borrow();
+    let _m = c.borrow_mut();
+}
+
+#[test]
+#[should_panic(expected = "already mutably borrowed")]
+fn borrow_while_borrowed_mut() {
+    let c = RefCell::new(1);
+    let _m = c.borrow_mut();
+    let _a = c.borrow();
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn two_mutable_borrows() {
+    let c = RefCell::new(1);
+    let _m = c.borrow_mut();
+    let _n = c.borrow_mut();
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn swap_with_itself() {
+    let c = RefCell::new(1);
+    c.swap(&c);
+}
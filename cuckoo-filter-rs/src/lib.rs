@@ -0,0 +1,199 @@
+//! Cuckoo filter: approximate set membership with deletion.
+//!
+//! Only short fingerprints of items are stored, in buckets of 4 slots.
+//! Each item has two candidate buckets, the second one derived from the
+//! first and the fingerprint alone, so a stored fingerprint can be moved
+//! to its other bucket without knowing the item. Insertion into two full
+//! buckets kicks a random fingerprint out to its other bucket, which may
+//! kick out another one and so on.
+//!
+//! Lookups can give false positives, but never false negatives. Removing
+//! an item that was never inserted may remove some other item sharing
+//! its fingerprint and bucket.
+//!
+//! See https://en.wikipedia.org/wiki/Cuckoo_filter
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+const BUCKET_SIZE: usize = 4;
+/// Relocations to try before giving up on insertion.
+const MAX_KICKS: usize = 500;
+/// Fingerprint 0 marks an empty slot.
+const EMPTY: u16 = 0;
+
+/// 64-bit FNV-1a, see https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Filter has no room left for the item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Full;
+
+impl fmt::Display for Full {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cuckoo filter is full")
+    }
+}
+
+impl std::error::Error for Full {}
+
+pub struct CuckooFilter<T: ?Sized> {
+    buckets: Vec<[u16; BUCKET_SIZE]>,
+    len: usize,
+    /// Fingerprint evicted by the last failed insertion, with its bucket.
+    /// Stored here so that the failure doesn't lose an earlier item.
+    victim: Option<(usize, u16)>,
+    rng: u64,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T: Hash + ?Sized> CuckooFilter<T> {
+    /// Creates filter with room for at least `capacity` items. In practice
+    /// insertions start failing only past 95% load.
+    pub fn new(capacity: usize) -> Self {
+        let n = capacity.div_ceil(BUCKET_SIZE).next_power_of_two();
+        Self {
+            buckets: vec![[EMPTY; BUCKET_SIZE]; n],
+            len: 0,
+            victim: None,
+            rng: 0x2545f4914f6cdd1d,
+            item: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    /// Fraction of occupied slots.
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.capacity() as f64
+    }
+
+    /// Fingerprint and first bucket of item.
+    fn locate(&self, item: &T) -> (u16, usize) {
+        let mut h = Fnv1a(0xcbf29ce484222325);
+        item.hash(&mut h);
+        let h = h.finish();
+        let fp = match (h >> 48) as u16 {
+            EMPTY => 1,
+            fp => fp,
+        };
+        (fp, h as usize & (self.buckets.len() - 1))
+    }
+
+    /// The other bucket of a fingerprint in bucket `i`. Works both ways
+    /// since it's a xor.
+    fn alt_bucket(&self, i: usize, fp: u16) -> usize {
+        (i ^ (fp as usize).wrapping_mul(0x5bd1e995)) & (self.buckets.len() - 1)
+    }
+
+    fn put(&mut self, i: usize, fp: u16) -> bool {
+        match self.buckets[i].iter_mut().find(|slot| **slot == EMPTY) {
+            Some(slot) => {
+                *slot = fp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Inserts item. Same item may be inserted several times, then it has
+    /// to be removed as many times.
+    pub fn insert(&mut self, item: &T) -> Result<(), Full> {
+        if self.victim.is_some() {
+            return Err(Full);
+        }
+        let (fp, i) = self.locate(item);
+        self.place(i, fp);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Puts fingerprint into bucket `i` or its other bucket, kicking out
+    /// others if both are full. If the kicks don't end, the last kicked
+    /// out fingerprint becomes the victim.
+    fn place(&mut self, i: usize, mut fp: u16) {
+        let j = self.alt_bucket(i, fp);
+        if self.put(i, fp) || self.put(j, fp) {
+            return;
+        }
+        let mut i = if self.random() & 1 == 0 { i } else { j };
+        for _ in 0..MAX_KICKS {
+            let slot = self.random() as usize % BUCKET_SIZE;
+            std::mem::swap(&mut fp, &mut self.buckets[i][slot]);
+            i = self.alt_bucket(i, fp);
+            if self.put(i, fp) {
+                return;
+            }
+        }
+        self.victim = Some((i, fp));
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        let (fp, i) = self.locate(item);
+        let j = self.alt_bucket(i, fp);
+        self.buckets[i].contains(&fp)
+            || self.buckets[j].contains(&fp)
+            || self
+                .victim
+                .is_some_and(|(k, v)| v == fp && (k == i || k == j))
+    }
+
+    /// Removes item, returns whether it was found. Item must have been
+    /// inserted, see crate docs.
+    pub fn remove(&mut self, item: &T) -> bool {
+        let (fp, i) = self.locate(item);
+        let j = self.alt_bucket(i, fp);
+        if self
+            .victim
+            .is_some_and(|(k, v)| v == fp && (k == i || k == j))
+        {
+            self.victim = None;
+            self.len -= 1;
+            return true;
+        }
+        for b in [i, j] {
+            if let Some(slot) = self.buckets[b].iter_mut().find(|slot| **slot == fp) {
+                *slot = EMPTY;
+                self.len -= 1;
+                // room was freed, kick the victim towards it, the free
+                // slot needn't be in one of its buckets
+                if let Some((k, v)) = self.victim.take() {
+                    self.place(k, v);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// xorshift64 for choosing fingerprints to kick out
+    fn random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+}
@@ -0,0 +1,122 @@
+//! Filled up until insertion fails: every inserted item is still found,
+//! through the victim slot too, and removals make room again.
+
+use std::collections::HashSet;
+
+use cuckoo_filter::{CuckooFilter, Full};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Inserts random keys until the filter is full, returns the inserted.
+fn fill(filter: &mut CuckooFilter<u64>, rng: &mut Rng) -> Vec<u64> {
+    let mut inserted = Vec::new();
+    loop {
+        let key = rng.next();
+        match filter.insert(&key) {
+            Ok(()) => inserted.push(key),
+            Err(Full) => return inserted,
+        }
+    }
+}
+
+fn assert_all_found(filter: &CuckooFilter<u64>, keys: &[u64]) {
+    for key in keys {
+        assert!(filter.contains(key), "false negative for {}", key);
+    }
+}
+
+#[test]
+fn no_false_negatives_until_full() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for capacity in [0, 1, 4, 5, 100, 1000, 1 << 14] {
+        let mut filter = CuckooFilter::new(capacity);
+        assert!(filter.capacity() >= capacity);
+        let inserted = fill(&mut filter, &mut rng);
+        assert_eq!(filter.len(), inserted.len());
+        assert_all_found(&filter, &inserted);
+        if capacity >= 1000 {
+            assert!(filter.load_factor() > 0.9, "load {}", filter.load_factor());
+        }
+        // stays full
+        assert_eq!(filter.insert(&rng.next()), Err(Full));
+        assert_eq!(filter.len(), inserted.len());
+    }
+}
+
+#[test]
+fn removal_makes_room_for_victim() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for round in 0..20 {
+        let mut filter = CuckooFilter::new(256 + round);
+        let mut keys = fill(&mut filter, &mut rng);
+        let mut removed = 0;
+        // remove random keys until the victim has a place again
+        loop {
+            let k = keys.swap_remove(rng.below(keys.len() as u64) as usize);
+            assert!(filter.remove(&k));
+            removed += 1;
+            assert_eq!(filter.len(), keys.len());
+            assert_all_found(&filter, &keys);
+            let key = rng.next();
+            if filter.insert(&key).is_ok() {
+                keys.push(key);
+                break;
+            }
+        }
+        assert!(removed <= 3, "{} removals before an insert fit", removed);
+        assert_all_found(&filter, &keys);
+        // everything comes out, victim included
+        while let Some(k) = keys.pop() {
+            assert!(filter.remove(&k));
+            assert_all_found(&filter, &keys);
+        }
+        assert!(filter.is_empty());
+        assert!(fill(&mut filter, &mut rng).len() > 200);
+    }
+}
+
+#[test]
+fn duplicates_and_false_positives() {
+    let mut filter = CuckooFilter::new(1000);
+    for _ in 0..3 {
+        filter.insert("same").unwrap();
+    }
+    assert_eq!(filter.len(), 3);
+    for _ in 0..3 {
+        assert!(filter.contains("same"));
+        assert!(filter.remove("same"));
+    }
+    assert!(!filter.contains("same"));
+    assert!(!filter.remove("same"));
+
+    let mut rng = Rng(7);
+    let inserted: HashSet<u64> = (0..900).map(|_| rng.next()).collect();
+    let mut filter = CuckooFilter::new(1000);
+    for key in &inserted {
+        filter.insert(key).unwrap();
+    }
+    let trials = 100_000;
+    let false_positives = (0..trials)
+        .map(|_| rng.next())
+        .filter(|k| !inserted.contains(k) && filter.contains(k))
+        .count();
+    // 16-bit fingerprints checked in 8 slots give about 0.012%
+    assert!(
+        false_positives < trials / 1000,
+        "{} false positives",
+        false_positives
+    );
+}
@@ -0,0 +1,132 @@
+//! A* shortest path search.
+//!
+//! Like Dijkstra's algorithm, but nodes are expanded in order of path
+//! length so far plus heuristic estimate of the rest. The path found is
+//! shortest as long as the heuristic is admissible, i.e. never
+//! overestimates. Edge weights must not be negative.
+//!
+//! Debug builds check that the heuristic doesn't overestimate remaining
+//! length for any node on the found path.
+//!
+//! See https://en.wikipedia.org/wiki/A*_search_algorithm
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{Cost, EdgeType, Graph, NodeIndex};
+
+/// Node in the open set, ordered for min-heap by estimate.
+struct Open<C> {
+    estimate: C,
+    cost: C,
+    node: NodeIndex,
+}
+
+impl<C: Cost> PartialEq for Open<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: Cost> Eq for Open<C> {}
+
+impl<C: Cost> PartialOrd for Open<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Cost> Ord for Open<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Shortest path from `start` to `goal` with its length, `None` if goal
+/// is unreachable. Edge weights are lengths, `heuristic` estimates
+/// length of the path from a node to goal.
+pub fn astar<N, C, Ty, H>(
+    graph: &Graph<N, C, Ty>,
+    start: NodeIndex,
+    goal: NodeIndex,
+    mut heuristic: H,
+) -> Option<(C, Vec<NodeIndex>)>
+where
+    C: Cost,
+    Ty: EdgeType,
+    H: FnMut(NodeIndex) -> C,
+{
+    let n = graph.node_count();
+    let mut cost: Vec<Option<C>> = vec![None; n];
+    let mut pred = vec![None; n];
+    let mut open = BinaryHeap::new();
+
+    cost[start.index()] = Some(C::zero());
+    open.push(Open {
+        estimate: heuristic(start),
+        cost: C::zero(),
+        node: start,
+    });
+    while let Some(Open { cost: c, node: u, .. }) = open.pop() {
+        if u == goal {
+            let mut path = vec![goal];
+            let mut n = goal;
+            while let Some(p) = pred[n.index()] {
+                path.push(p);
+                n = p;
+            }
+            path.reverse();
+            #[cfg(debug_assertions)]
+            check_admissible(graph, &path, c, &mut heuristic);
+            return Some((c, path));
+        }
+        // stale entry, node was reached by a shorter path since
+        if cost[u.index()].is_some_and(|best| best < c) {
+            continue;
+        }
+        for e in graph.edges(u) {
+            let v = e.target;
+            let c = c + *e.weight;
+            if cost[v.index()].is_some_and(|best| best <= c) {
+                continue;
+            }
+            cost[v.index()] = Some(c);
+            pred[v.index()] = Some(u);
+            open.push(Open {
+                estimate: c + heuristic(v),
+                cost: c,
+                node: v,
+            });
+        }
+    }
+    None
+}
+
+#[cfg(debug_assertions)]
+fn check_admissible<N, C, Ty, H>(graph: &Graph<N, C, Ty>, path: &[NodeIndex], len: C, heuristic: &mut H)
+where
+    C: Cost,
+    Ty: EdgeType,
+    H: FnMut(NodeIndex) -> C,
+{
+    let mut done = C::zero();
+    for (i, &n) in path.iter().enumerate() {
+        assert!(
+            heuristic(n) <= len - done,
+            "heuristic overestimates distance to goal at node {:?}",
+            n
+        );
+        if let Some(&next) = path.get(i + 1) {
+            done = done
+                + graph
+                    .edges(n)
+                    .filter(|e| e.target == next)
+                    .map(|e| *e.weight)
+                    .reduce(|a, b| if b < a { b } else { a })
+                    .unwrap();
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Single-source shortest paths with negative edge weights.
+//!
+//! Instead of relaxing all edges n - 1 times, only edges leaving nodes
+//! whose distance changed get relaxed, with such nodes kept in a queue
+//! (SPFA). With a negative cycle reachable from source relaxations never
+//! stop, but the predecessor graph eventually gets a cycle, which is
+//! then always negative. So predecessor graph is checked for a cycle
+//! every n relaxations, keeping the check's cost amortized O(1).
+//!
+//! See https://en.wikipedia.org/wiki/Bellman%E2%80%93Ford_algorithm
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::{Cost, EdgeType, Graph, NodeIndex};
+
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<C> {
+    dist: Vec<Option<C>>,
+    pred: Vec<Option<NodeIndex>>,
+}
+
+impl<C: Cost> ShortestPaths<C> {
+    /// Length of the shortest path to `n`, `None` if unreachable.
+    pub fn distance(&self, n: NodeIndex) -> Option<C> {
+        self.dist[n.index()]
+    }
+
+    /// Node preceding `n` on its shortest path.
+    pub fn predecessor(&self, n: NodeIndex) -> Option<NodeIndex> {
+        self.pred[n.index()]
+    }
+
+    pub fn path_to(&self, n: NodeIndex) -> Option<Vec<NodeIndex>> {
+        self.dist[n.index()]?;
+        let mut path = vec![n];
+        let mut n = n;
+        while let Some(p) = self.pred[n.index()] {
+            path.push(p);
+            n = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Negative cycle reachable from source. Nodes follow edge direction,
+/// the last node connects back to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NegativeCycle(pub Vec<NodeIndex>);
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "negative cycle of {} nodes", self.0.len())
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+/// Shortest paths from `source` with edge weights as lengths.
+pub fn bellman_ford<N, C: Cost, Ty: EdgeType>(
+    graph: &Graph<N, C, Ty>,
+    source: NodeIndex,
+) -> Result<ShortestPaths<C>, NegativeCycle> {
+    let n = graph.node_count();
+    let mut sp = ShortestPaths {
+        dist: vec![None; n],
+        pred: vec![None; n],
+    };
+    let mut queue = VecDeque::from([source]);
+    let mut in_queue = vec![false; n];
+    let mut relaxations = 0usize;
+
+    sp.dist[source.index()] = Some(C::zero());
+    in_queue[source.index()] = true;
+    while let Some(u) = queue.pop_front() {
+        in_queue[u.index()] = false;
+        let du = sp.dist[u.index()].unwrap();
+        for e in graph.edges(u) {
+            let v = e.target.index();
+            let d = du + *e.weight;
+            if sp.dist[v].is_some_and(|dv| dv <= d) {
+                continue;
+            }
+            sp.dist[v] = Some(d);
+            sp.pred[v] = Some(u);
+            relaxations += 1;
+            if relaxations.is_multiple_of(n) {
+                if let Some(cycle) = pred_cycle(&sp.pred) {
+                    return Err(NegativeCycle(cycle));
+                }
+            }
+            if !in_queue[v] {
+                in_queue[v] = true;
+                queue.push_back(e.target);
+            }
+        }
+    }
+    Ok(sp)
+}
+
+/// Finds a cycle in the graph of predecessor links.
+fn pred_cycle(pred: &[Option<NodeIndex>]) -> Option<Vec<NodeIndex>> {
+    const NEW: u8 = 0;
+    const ON_WALK: u8 = 1;
+    const DONE: u8 = 2;
+    let mut state = vec![NEW; pred.len()];
+    let mut walk = Vec::new();
+
+    for start in 0..pred.len() {
+        let mut n = Some(NodeIndex::new(start));
+        walk.clear();
+        while let Some(i) = n {
+            match state[i.index()] {
+                NEW => {
+                    state[i.index()] = ON_WALK;
+                    walk.push(i);
+                    n = pred[i.index()];
+                }
+                ON_WALK => {
+                    // walk goes against edges, reverse to follow them
+                    let pos = walk.iter().position(|&j| j == i).unwrap();
+                    let mut cycle = walk.split_off(pos);
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+                _ => break,
+            }
+        }
+        for i in &walk {
+            state[i.index()] = DONE;
+        }
+    }
+    None
+}
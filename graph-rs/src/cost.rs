@@ -0,0 +1,19 @@
+use std::ops::{Add, Sub};
+
+/// Edge weights usable as path lengths or capacities.
+pub trait Cost: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> {
+    fn zero() -> Self;
+}
+
+macro_rules! impl_cost {
+    ($zero:expr; $($t:ty),*) => {$(
+        impl Cost for $t {
+            fn zero() -> Self {
+                $zero
+            }
+        }
+    )*};
+}
+
+impl_cost!(0; i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_cost!(0.0; f32, f64);
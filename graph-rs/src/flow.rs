@@ -0,0 +1,169 @@
+//! Maximum flow in a network with edge weights as capacities.
+//!
+//! Dinic's algorithm: each phase builds a level graph of the residual
+//! network with BFS from source, then saturates it with a blocking flow
+//! found by DFS along edges going one level deeper. Phases stop when
+//! sink becomes unreachable, at which point nodes still reachable from
+//! source form the source side of a minimum cut.
+//!
+//! See https://en.wikipedia.org/wiki/Dinic%27s_algorithm
+
+use std::collections::VecDeque;
+
+use crate::{Cost, Directed, EdgeIndex, Graph, NodeIndex};
+
+#[derive(Clone, Debug)]
+pub struct MaxFlow<C> {
+    value: C,
+    flow: Vec<C>,
+    source_side: Vec<bool>,
+    cut: Vec<EdgeIndex>,
+}
+
+impl<C: Cost> MaxFlow<C> {
+    pub fn value(&self) -> C {
+        self.value
+    }
+
+    /// Flow along edge `e`.
+    pub fn flow(&self, e: EdgeIndex) -> C {
+        self.flow[e.index()]
+    }
+
+    /// Whether `n` is on source side of the minimum cut.
+    pub fn is_source_side(&self, n: NodeIndex) -> bool {
+        self.source_side[n.index()]
+    }
+
+    /// Edges of the minimum cut. Their capacities add up to the flow value.
+    pub fn min_cut(&self) -> &[EdgeIndex] {
+        &self.cut
+    }
+}
+
+/// Residual network. Arc `2 * i` goes along edge `i`, arc `2 * i + 1`
+/// goes backwards, so `a ^ 1` is the arc opposite to `a`.
+struct Residual<C> {
+    head: Vec<usize>,
+    residual: Vec<C>,
+    arcs: Vec<Vec<usize>>,
+    level: Vec<Option<usize>>,
+}
+
+impl<C: Cost> Residual<C> {
+    fn tail(&self, a: usize) -> usize {
+        self.head[a ^ 1]
+    }
+
+    /// Computes levels of nodes reachable from `s`, returns whether `t`
+    /// is among them.
+    fn bfs(&mut self, s: usize, t: usize) -> bool {
+        self.level.fill(None);
+        self.level[s] = Some(0);
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            let next = self.level[u].map(|l| l + 1);
+            for &a in &self.arcs[u] {
+                let v = self.head[a];
+                if self.level[v].is_none() && self.residual[a] > C::zero() {
+                    self.level[v] = next;
+                    queue.push_back(v);
+                }
+            }
+        }
+        self.level[t].is_some()
+    }
+
+    fn is_admissible(&self, a: usize) -> bool {
+        let (u, v) = (self.tail(a), self.head[a]);
+        self.residual[a] > C::zero() && self.level[v].is_some() && self.level[v] == self.level[u].map(|l| l + 1)
+    }
+
+    /// Saturates level graph, returns flow pushed.
+    fn blocking_flow(&mut self, s: usize, t: usize) -> C {
+        let mut total = C::zero();
+        // next arc to try for each node
+        let mut next = vec![0; self.arcs.len()];
+        let mut path: Vec<usize> = Vec::new();
+        let mut u = s;
+        loop {
+            if u == t {
+                let pushed = path
+                    .iter()
+                    .map(|&a| self.residual[a])
+                    .reduce(|a, b| if b < a { b } else { a })
+                    .unwrap();
+                for &a in &path {
+                    self.residual[a] = self.residual[a] - pushed;
+                    self.residual[a ^ 1] = self.residual[a ^ 1] + pushed;
+                }
+                total = total + pushed;
+                // retreat to the tail of the first saturated arc
+                let k = path.iter().position(|&a| self.residual[a] == C::zero()).unwrap();
+                path.truncate(k);
+                u = path.last().map_or(s, |&a| self.head[a]);
+                continue;
+            }
+            while next[u] < self.arcs[u].len() && !self.is_admissible(self.arcs[u][next[u]]) {
+                next[u] += 1;
+            }
+            if let Some(&a) = self.arcs[u].get(next[u]) {
+                path.push(a);
+                u = self.head[a];
+                continue;
+            }
+            // dead end, nothing to push through u anymore
+            self.level[u] = None;
+            match path.pop() {
+                Some(a) => {
+                    u = self.tail(a);
+                    next[u] += 1;
+                }
+                None => return total,
+            }
+        }
+    }
+}
+
+/// Maximum flow from `s` to `t`. Capacities must not be negative.
+pub fn dinic<N, C: Cost>(graph: &Graph<N, C, Directed>, s: NodeIndex, t: NodeIndex) -> MaxFlow<C> {
+    assert!(s != t, "source and sink must differ");
+    let n = graph.node_count();
+    let mut net = Residual {
+        head: Vec::with_capacity(2 * graph.edge_count()),
+        residual: Vec::with_capacity(2 * graph.edge_count()),
+        arcs: vec![Vec::new(); n],
+        level: vec![None; n],
+    };
+    for e in graph.edge_indices() {
+        let (u, v) = graph.edge_endpoints(e).unwrap();
+        net.arcs[u.index()].push(net.head.len());
+        net.head.push(v.index());
+        net.residual.push(graph[e]);
+        net.arcs[v.index()].push(net.head.len());
+        net.head.push(u.index());
+        net.residual.push(C::zero());
+    }
+
+    let mut value = C::zero();
+    while net.bfs(s.index(), t.index()) {
+        value = value + net.blocking_flow(s.index(), t.index());
+    }
+
+    // last bfs didn't reach sink, its levels mark the source side
+    let source_side: Vec<bool> = net.level.iter().map(|l| l.is_some()).collect();
+    let flow = graph.edge_indices().map(|e| net.residual[2 * e.index() + 1]).collect();
+    let cut = graph
+        .edge_indices()
+        .filter(|&e| {
+            let (u, v) = graph.edge_endpoints(e).unwrap();
+            source_side[u.index()] && !source_side[v.index()]
+        })
+        .collect();
+    MaxFlow {
+        value,
+        flow,
+        source_side,
+        cut,
+    }
+}
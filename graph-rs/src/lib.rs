@@ -0,0 +1,257 @@
+//! Graph stored as adjacency lists.
+//!
+//! Nodes and edges are kept in vectors and referred to by indices, which
+//! stay valid for the graph's whole life since nothing is ever removed.
+//!
+//! See https://en.wikipedia.org/wiki/Adjacency_list
+
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+mod astar;
+mod bellman_ford;
+mod cost;
+mod flow;
+mod scc;
+mod traverse;
+
+pub use astar::astar;
+pub use bellman_ford::{bellman_ford, NegativeCycle, ShortestPaths};
+pub use cost::Cost;
+pub use flow::{dinic, MaxFlow};
+pub use scc::{condense, tarjan_scc};
+pub use traverse::{Bfs, Dfs};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeIndex(usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeIndex(usize);
+
+impl NodeIndex {
+    pub fn new(i: usize) -> Self {
+        Self(i)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl EdgeIndex {
+    pub fn new(i: usize) -> Self {
+        Self(i)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+pub trait EdgeType {
+    fn is_directed() -> bool;
+}
+
+#[derive(Debug)]
+pub enum Directed {}
+
+#[derive(Debug)]
+pub enum Undirected {}
+
+impl EdgeType for Directed {
+    fn is_directed() -> bool {
+        true
+    }
+}
+
+impl EdgeType for Undirected {
+    fn is_directed() -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node<N> {
+    weight: N,
+    /// Outgoing edges, or all incident edges if undirected.
+    edges: Vec<EdgeIndex>,
+}
+
+#[derive(Clone, Debug)]
+struct Edge<E> {
+    weight: E,
+    source: NodeIndex,
+    target: NodeIndex,
+}
+
+/// Edge as seen from the node it was reached from.
+#[derive(Debug)]
+pub struct EdgeRef<'a, E> {
+    pub id: EdgeIndex,
+    pub source: NodeIndex,
+    pub target: NodeIndex,
+    pub weight: &'a E,
+}
+
+impl<E> Clone for EdgeRef<'_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for EdgeRef<'_, E> {}
+
+#[derive(Clone, Debug)]
+pub struct Graph<N, E, Ty: EdgeType = Directed> {
+    nodes: Vec<Node<N>>,
+    edges: Vec<Edge<E>>,
+    ty: PhantomData<Ty>,
+}
+
+impl<N, E> Graph<N, E, Directed> {
+    pub fn new() -> Self {
+        Self::with_edge_type()
+    }
+}
+
+impl<N, E> Default for Graph<N, E, Directed> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> Graph<N, E, Undirected> {
+    pub fn new_undirected() -> Self {
+        Self::with_edge_type()
+    }
+}
+
+impl<N, E, Ty: EdgeType> Graph<N, E, Ty> {
+    fn with_edge_type() -> Self {
+        Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            ty: PhantomData,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn add_node(&mut self, weight: N) -> NodeIndex {
+        self.nodes.push(Node {
+            weight,
+            edges: Vec::new(),
+        });
+        NodeIndex(self.nodes.len() - 1)
+    }
+
+    /// Adds edge from `a` to `b`. Parallel edges and loops are allowed.
+    pub fn add_edge(&mut self, a: NodeIndex, b: NodeIndex, weight: E) -> EdgeIndex {
+        assert!(
+            a.0 < self.nodes.len() && b.0 < self.nodes.len(),
+            "edge endpoint is not in the graph"
+        );
+        let e = EdgeIndex(self.edges.len());
+        self.edges.push(Edge {
+            weight,
+            source: a,
+            target: b,
+        });
+        self.nodes[a.0].edges.push(e);
+        if !Ty::is_directed() && a != b {
+            self.nodes[b.0].edges.push(e);
+        }
+        e
+    }
+
+    pub fn node_weight(&self, n: NodeIndex) -> Option<&N> {
+        self.nodes.get(n.0).map(|n| &n.weight)
+    }
+
+    pub fn node_weight_mut(&mut self, n: NodeIndex) -> Option<&mut N> {
+        self.nodes.get_mut(n.0).map(|n| &mut n.weight)
+    }
+
+    pub fn edge_weight(&self, e: EdgeIndex) -> Option<&E> {
+        self.edges.get(e.0).map(|e| &e.weight)
+    }
+
+    pub fn edge_weight_mut(&mut self, e: EdgeIndex) -> Option<&mut E> {
+        self.edges.get_mut(e.0).map(|e| &mut e.weight)
+    }
+
+    pub fn edge_endpoints(&self, e: EdgeIndex) -> Option<(NodeIndex, NodeIndex)> {
+        self.edges.get(e.0).map(|e| (e.source, e.target))
+    }
+
+    pub fn node_indices(&self) -> impl ExactSizeIterator<Item = NodeIndex> {
+        (0..self.nodes.len()).map(NodeIndex)
+    }
+
+    pub fn edge_indices(&self) -> impl ExactSizeIterator<Item = EdgeIndex> {
+        (0..self.edges.len()).map(EdgeIndex)
+    }
+
+    /// Edges leaving `n`, or all edges incident to `n` if undirected.
+    /// `source` of the yielded edges is always `n`.
+    pub fn edges(&self, n: NodeIndex) -> impl Iterator<Item = EdgeRef<'_, E>> + '_ {
+        self.nodes[n.0].edges.iter().map(move |&id| {
+            let e = &self.edges[id.0];
+            let target = if e.source == n { e.target } else { e.source };
+            EdgeRef {
+                id,
+                source: n,
+                target,
+                weight: &e.weight,
+            }
+        })
+    }
+
+    pub fn neighbors(&self, n: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.edges(n).map(|e| e.target)
+    }
+
+    /// `i`-th neighbor of `n`, same as `neighbors(n).nth(i)` but O(1).
+    pub(crate) fn neighbor_at(&self, n: NodeIndex, i: usize) -> Option<NodeIndex> {
+        let e = &self.edges[self.nodes[n.0].edges.get(i)?.0];
+        Some(if e.source == n { e.target } else { e.source })
+    }
+}
+
+impl<N, E, Ty: EdgeType> Index<NodeIndex> for Graph<N, E, Ty> {
+    type Output = N;
+
+    fn index(&self, n: NodeIndex) -> &N {
+        &self.nodes[n.0].weight
+    }
+}
+
+impl<N, E, Ty: EdgeType> IndexMut<NodeIndex> for Graph<N, E, Ty> {
+    fn index_mut(&mut self, n: NodeIndex) -> &mut N {
+        &mut self.nodes[n.0].weight
+    }
+}
+
+impl<N, E, Ty: EdgeType> Index<EdgeIndex> for Graph<N, E, Ty> {
+    type Output = E;
+
+    fn index(&self, e: EdgeIndex) -> &E {
+        &self.edges[e.0].weight
+    }
+}
+
+impl<N, E, Ty: EdgeType> IndexMut<EdgeIndex> for Graph<N, E, Ty> {
+    fn index_mut(&mut self, e: EdgeIndex) -> &mut E {
+        &mut self.edges[e.0].weight
+    }
+}
@@ -0,0 +1,102 @@
+//! Strongly connected components.
+//!
+//! Tarjan's algorithm done with an explicit stack instead of recursion,
+//! so that deep graphs don't overflow the call stack.
+//!
+//! See https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm
+
+use crate::{Directed, EdgeType, Graph, NodeIndex};
+
+/// Strongly connected components in reverse topological order, i.e.
+/// edges between components only go from later to earlier ones.
+pub fn tarjan_scc<N, E, Ty: EdgeType>(graph: &Graph<N, E, Ty>) -> Vec<Vec<NodeIndex>> {
+    const UNVISITED: usize = usize::MAX;
+    let n = graph.node_count();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    // nodes being visited, with position of the next neighbor to look at
+    let mut calls: Vec<(NodeIndex, usize)> = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+
+    for root in graph.node_indices() {
+        if index[root.index()] != UNVISITED {
+            continue;
+        }
+        calls.push((root, 0));
+        while let Some(&mut (v, ref mut pos)) = calls.last_mut() {
+            let vi = v.index();
+            if *pos == 0 {
+                index[vi] = next_index;
+                low[vi] = next_index;
+                next_index += 1;
+                stack.push(v);
+                on_stack[vi] = true;
+            }
+            if let Some(w) = graph.neighbor_at(v, *pos) {
+                *pos += 1;
+                let wi = w.index();
+                if index[wi] == UNVISITED {
+                    calls.push((w, 0));
+                } else if on_stack[wi] {
+                    low[vi] = low[vi].min(index[wi]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent.index()] = low[parent.index()].min(low[vi]);
+            }
+            if low[vi] == index[vi] {
+                let mut component = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w.index()] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Graph of strongly connected components, which is always acyclic.
+///
+/// Node `i` of the result holds nodes of the `i`-th component returned
+/// by `tarjan_scc`. Components get an edge if any of their nodes are
+/// connected, parallel edges are merged.
+pub fn condense<N, E, Ty: EdgeType>(graph: &Graph<N, E, Ty>) -> Graph<Vec<NodeIndex>, ()> {
+    let components = tarjan_scc(graph);
+    let mut component_of = vec![0; graph.node_count()];
+    for (i, c) in components.iter().enumerate() {
+        for n in c {
+            component_of[n.index()] = i;
+        }
+    }
+
+    let mut dag = Graph::<_, _, Directed>::new();
+    for c in &components {
+        dag.add_node(c.clone());
+    }
+    // last source component each target got an edge from
+    let mut linked_from = vec![usize::MAX; components.len()];
+    for (i, c) in components.iter().enumerate() {
+        for &n in c {
+            for m in graph.neighbors(n) {
+                let j = component_of[m.index()];
+                if j != i && linked_from[j] != i {
+                    linked_from[j] = i;
+                    dag.add_edge(NodeIndex::new(i), NodeIndex::new(j), ());
+                }
+            }
+        }
+    }
+    dag
+}
@@ -0,0 +1,157 @@
+//! Breadth- and depth-first traversals.
+//!
+//! Both are iterators yielding nodes in discovery order. While iterating
+//! (and after), the traversal tree can be inspected with `parent()` and
+//! `distance()`, where distance is the number of edges from the start
+//! node along the tree.
+
+use std::collections::VecDeque;
+
+use crate::{EdgeType, Graph, NodeIndex};
+
+/// Records discovery of nodes shared by both traversals.
+struct Tree {
+    parent: Vec<Option<NodeIndex>>,
+    dist: Vec<Option<usize>>,
+}
+
+impl Tree {
+    fn new(len: usize, start: NodeIndex) -> Self {
+        let mut dist = vec![None; len];
+        dist[start.index()] = Some(0);
+        Self {
+            parent: vec![None; len],
+            dist,
+        }
+    }
+
+    fn is_discovered(&self, n: NodeIndex) -> bool {
+        self.dist[n.index()].is_some()
+    }
+
+    fn discover(&mut self, n: NodeIndex, parent: NodeIndex) {
+        self.parent[n.index()] = Some(parent);
+        self.dist[n.index()] = Some(self.dist[parent.index()].unwrap() + 1);
+    }
+
+    /// Path from start to `n` following parent links.
+    fn path_to(&self, n: NodeIndex) -> Option<Vec<NodeIndex>> {
+        self.dist[n.index()]?;
+        let mut path = vec![n];
+        let mut n = n;
+        while let Some(p) = self.parent[n.index()] {
+            path.push(p);
+            n = p;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+pub struct Bfs<'a, N, E, Ty: EdgeType> {
+    graph: &'a Graph<N, E, Ty>,
+    queue: VecDeque<NodeIndex>,
+    tree: Tree,
+}
+
+impl<'a, N, E, Ty: EdgeType> Bfs<'a, N, E, Ty> {
+    pub fn new(graph: &'a Graph<N, E, Ty>, start: NodeIndex) -> Self {
+        Self {
+            graph,
+            queue: VecDeque::from([start]),
+            tree: Tree::new(graph.node_count(), start),
+        }
+    }
+
+    /// Node `n` was discovered from, `None` for start and undiscovered nodes.
+    pub fn parent(&self, n: NodeIndex) -> Option<NodeIndex> {
+        self.tree.parent[n.index()]
+    }
+
+    /// Length of the shortest path to `n` in edges, `None` if undiscovered.
+    pub fn distance(&self, n: NodeIndex) -> Option<usize> {
+        self.tree.dist[n.index()]
+    }
+
+    pub fn path_to(&self, n: NodeIndex) -> Option<Vec<NodeIndex>> {
+        self.tree.path_to(n)
+    }
+}
+
+impl<N, E, Ty: EdgeType> Iterator for Bfs<'_, N, E, Ty> {
+    type Item = NodeIndex;
+
+    fn next(&mut self) -> Option<NodeIndex> {
+        let n = self.queue.pop_front()?;
+        for m in self.graph.neighbors(n) {
+            if !self.tree.is_discovered(m) {
+                self.tree.discover(m, n);
+                self.queue.push_back(m);
+            }
+        }
+        Some(n)
+    }
+}
+
+pub struct Dfs<'a, N, E, Ty: EdgeType> {
+    graph: &'a Graph<N, E, Ty>,
+    /// Path from start to the current node, with position of the next
+    /// neighbor to look at for each node on it.
+    stack: Vec<(NodeIndex, usize)>,
+    start: Option<NodeIndex>,
+    tree: Tree,
+}
+
+impl<'a, N, E, Ty: EdgeType> Dfs<'a, N, E, Ty> {
+    pub fn new(graph: &'a Graph<N, E, Ty>, start: NodeIndex) -> Self {
+        Self {
+            graph,
+            stack: Vec::new(),
+            start: Some(start),
+            tree: Tree::new(graph.node_count(), start),
+        }
+    }
+
+    /// Node `n` was discovered from, `None` for start and undiscovered nodes.
+    pub fn parent(&self, n: NodeIndex) -> Option<NodeIndex> {
+        self.tree.parent[n.index()]
+    }
+
+    /// Depth of `n` in the search tree, `None` if undiscovered.
+    pub fn distance(&self, n: NodeIndex) -> Option<usize> {
+        self.tree.dist[n.index()]
+    }
+
+    pub fn path_to(&self, n: NodeIndex) -> Option<Vec<NodeIndex>> {
+        self.tree.path_to(n)
+    }
+}
+
+impl<N, E, Ty: EdgeType> Iterator for Dfs<'_, N, E, Ty> {
+    type Item = NodeIndex;
+
+    fn next(&mut self) -> Option<NodeIndex> {
+        if let Some(start) = self.start.take() {
+            self.stack.push((start, 0));
+            return Some(start);
+        }
+        // same order as recursive dfs: go down the first undiscovered
+        // neighbor, backtrack when a node has none left
+        while let Some((n, pos)) = self.stack.last_mut() {
+            match self.graph.neighbor_at(*n, *pos) {
+                Some(m) => {
+                    *pos += 1;
+                    if !self.tree.is_discovered(m) {
+                        self.tree.discover(m, *n);
+                        self.stack.push((m, 0));
+                        return Some(m);
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,169 @@
+//! A* finds paths as short as Dijkstra's with any admissible heuristic,
+//! and debug builds catch a heuristic that overestimates.
+
+use graph::{astar, Graph, NodeIndex, Undirected};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn n(i: usize) -> NodeIndex {
+    NodeIndex::new(i)
+}
+
+/// Plain O(n^2) Dijkstra, distances from `source` along edges reversed
+/// if `reverse`.
+fn dijkstra(graph: &Graph<(), u32>, source: NodeIndex, reverse: bool) -> Vec<Option<u32>> {
+    let len = graph.node_count();
+    let mut dist = vec![None; len];
+    let mut done = vec![false; len];
+    dist[source.index()] = Some(0);
+    while let Some(u) = (0..len)
+        .filter(|&i| !done[i] && dist[i].is_some())
+        .min_by_key(|&i| dist[i])
+    {
+        done[u] = true;
+        for e in graph.edge_indices() {
+            let (mut a, mut b) = graph.edge_endpoints(e).unwrap();
+            if reverse {
+                (a, b) = (b, a);
+            }
+            if a.index() == u {
+                let d = dist[u].unwrap() + graph[e];
+                if dist[b.index()].is_none_or(|db| db > d) {
+                    dist[b.index()] = Some(d);
+                }
+            }
+        }
+    }
+    dist
+}
+
+fn random_graph(rng: &mut Rng) -> Graph<(), u32> {
+    let mut graph = Graph::new();
+    let nodes = 1 + rng.below(30) as usize;
+    let edges = rng.below(4 * nodes as u64) as usize;
+    for _ in 0..nodes {
+        graph.add_node(());
+    }
+    for _ in 0..edges {
+        let a = rng.below(nodes as u64) as usize;
+        let b = rng.below(nodes as u64) as usize;
+        graph.add_edge(n(a), n(b), rng.below(20) as u32);
+    }
+    graph
+}
+
+fn path_len(graph: &Graph<(), u32>, path: &[NodeIndex]) -> u32 {
+    path.windows(2)
+        .map(|w| {
+            graph
+                .edges(w[0])
+                .filter(|e| e.target == w[1])
+                .map(|e| *e.weight)
+                .min()
+                .expect("no edge between consecutive nodes")
+        })
+        .sum()
+}
+
+#[test]
+fn same_cost_as_dijkstra() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..2000 {
+        let g = random_graph(&mut rng);
+        let start = n(rng.below(g.node_count() as u64) as usize);
+        let goal = n(rng.below(g.node_count() as u64) as usize);
+        let expected = dijkstra(&g, start, false)[goal.index()];
+        // exact remaining distance is the best admissible heuristic
+        let to_goal = dijkstra(&g, goal, true);
+        let exact = |v: NodeIndex| to_goal[v.index()].unwrap_or(0);
+        let halved = |v: NodeIndex| to_goal[v.index()].unwrap_or(0) / 2;
+        for found in [
+            astar(&g, start, goal, |_| 0),
+            astar(&g, start, goal, exact),
+            astar(&g, start, goal, halved),
+        ] {
+            match found {
+                Some((cost, path)) => {
+                    assert_eq!(Some(cost), expected);
+                    assert_eq!(path.first(), Some(&start));
+                    assert_eq!(path.last(), Some(&goal));
+                    assert_eq!(path_len(&g, &path), cost);
+                }
+                None => assert_eq!(expected, None),
+            }
+        }
+    }
+}
+
+#[test]
+fn grid_with_manhattan_distance() {
+    let (w, h) = (30, 20);
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    let mut g = Graph::<(usize, usize), u32, Undirected>::new_undirected();
+    for y in 0..h {
+        for x in 0..w {
+            g.add_node((x, y));
+        }
+    }
+    for y in 0..h {
+        for x in 0..w {
+            // every step costs at least 1, some are walls
+            if x + 1 < w && rng.below(5) != 0 {
+                g.add_edge(n(y * w + x), n(y * w + x + 1), 1 + rng.below(3) as u32);
+            }
+            if y + 1 < h && rng.below(5) != 0 {
+                g.add_edge(n(y * w + x), n((y + 1) * w + x), 1 + rng.below(3) as u32);
+            }
+        }
+    }
+    let goal = n(w * h - 1);
+    let manhattan = |v: NodeIndex| {
+        let (x, y) = g[v];
+        ((w - 1 - x) + (h - 1 - y)) as u32
+    };
+    let with = astar(&g, n(0), goal, manhattan);
+    let without = astar(&g, n(0), goal, |_| 0);
+    assert!(with.is_some());
+    assert_eq!(with.as_ref().map(|r| r.0), without.as_ref().map(|r| r.0));
+}
+
+#[test]
+fn float_costs() {
+    let mut g = Graph::new();
+    let a = g.add_node(());
+    let b = g.add_node(());
+    let c = g.add_node(());
+    g.add_edge(a, b, 0.5);
+    g.add_edge(b, c, 0.25);
+    g.add_edge(a, c, 1.0);
+    assert_eq!(astar(&g, a, c, |_| 0.0), Some((0.75, vec![a, b, c])));
+    assert_eq!(astar(&g, c, a, |_| 0.0), None);
+    assert_eq!(astar(&g, a, a, |_| 0.0), Some((0.0, vec![a])));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "heuristic overestimates")]
+fn overestimate_caught() {
+    let mut g = Graph::new();
+    let a = g.add_node(());
+    let b = g.add_node(());
+    let c = g.add_node(());
+    g.add_edge(a, b, 1);
+    g.add_edge(b, c, 1);
+    // claims b is further from goal than the whole path
+    astar(&g, a, c, |v| if v == b { 5 } else { 0 });
+}
@@ -0,0 +1,185 @@
+//! Bellman-Ford against the textbook version relaxing every edge n - 1
+//! times, and negative cycles checked to be real cycles of negative
+//! length reachable from source.
+
+use graph::{bellman_ford, EdgeType, Graph, NegativeCycle, NodeIndex};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn n(i: usize) -> NodeIndex {
+    NodeIndex::new(i)
+}
+
+/// Random graph with weights in `min..min + 25`.
+fn random_graph<Ty: EdgeType>(
+    mut graph: Graph<(), i64, Ty>,
+    rng: &mut Rng,
+    min: i64,
+) -> Graph<(), i64, Ty> {
+    let nodes = 1 + rng.below(12) as usize;
+    let edges = rng.below(3 * nodes as u64) as usize;
+    for _ in 0..nodes {
+        graph.add_node(());
+    }
+    for _ in 0..edges {
+        let a = rng.below(nodes as u64) as usize;
+        let b = rng.below(nodes as u64) as usize;
+        graph.add_edge(n(a), n(b), min + rng.below(25) as i64);
+    }
+    graph
+}
+
+/// Distances after n - 1 rounds over all edges, `None` if a further
+/// round still relaxes something, i.e. there is a negative cycle.
+fn naive<Ty: EdgeType>(graph: &Graph<(), i64, Ty>, source: NodeIndex) -> Option<Vec<Option<i64>>> {
+    let mut dist = vec![None; graph.node_count()];
+    dist[source.index()] = Some(0);
+    let relax = |dist: &mut Vec<Option<i64>>| {
+        let mut changed = false;
+        for u in graph.node_indices() {
+            let Some(du) = dist[u.index()] else { continue };
+            for e in graph.edges(u) {
+                let d = du + e.weight;
+                if dist[e.target.index()].is_none_or(|dv| dv > d) {
+                    dist[e.target.index()] = Some(d);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    };
+    for _ in 1..graph.node_count() {
+        relax(&mut dist);
+    }
+    (!relax(&mut dist)).then_some(dist)
+}
+
+/// Length of the shortest edge from `a` to `b`.
+fn edge_len<Ty: EdgeType>(graph: &Graph<(), i64, Ty>, a: NodeIndex, b: NodeIndex) -> i64 {
+    graph
+        .edges(a)
+        .filter(|e| e.target == b)
+        .map(|e| *e.weight)
+        .min()
+        .expect("no edge between consecutive nodes")
+}
+
+fn check<Ty: EdgeType>(graph: &Graph<(), i64, Ty>, source: NodeIndex) -> bool {
+    let expected = naive(graph, source);
+    match (bellman_ford(graph, source), expected) {
+        (Ok(sp), Some(dist)) => {
+            for i in graph.node_indices() {
+                assert_eq!(sp.distance(i), dist[i.index()]);
+                let Some(path) = sp.path_to(i) else {
+                    assert!(dist[i.index()].is_none());
+                    continue;
+                };
+                assert_eq!(path[0], source);
+                let len: i64 = path.windows(2).map(|w| edge_len(graph, w[0], w[1])).sum();
+                assert_eq!(Some(len), dist[i.index()]);
+                if i != source {
+                    assert_eq!(sp.predecessor(i), Some(path[path.len() - 2]));
+                }
+            }
+            false
+        }
+        (Err(NegativeCycle(cycle)), None) => {
+            assert!(!cycle.is_empty());
+            let mut nodes = cycle.clone();
+            nodes.sort();
+            nodes.dedup();
+            assert_eq!(nodes.len(), cycle.len(), "cycle repeats a node");
+            let len: i64 = (0..cycle.len())
+                .map(|i| edge_len(graph, cycle[i], cycle[(i + 1) % cycle.len()]))
+                .sum();
+            assert!(len < 0, "cycle is not negative");
+            // reachable from source
+            let sp = bellman_ford(&nonnegative(graph), source).unwrap();
+            assert!(sp.distance(cycle[0]).is_some());
+            true
+        }
+        (got, expected) => panic!("got {:?}, expected {:?}", got.map(|_| ()), expected),
+    }
+}
+
+/// Same edges, all of length 1.
+fn nonnegative<Ty: EdgeType>(graph: &Graph<(), i64, Ty>) -> Graph<(), i64> {
+    let mut g = Graph::new();
+    for _ in graph.node_indices() {
+        g.add_node(());
+    }
+    for e in graph.edge_indices() {
+        let (a, b) = graph.edge_endpoints(e).unwrap();
+        g.add_edge(a, b, 1);
+        if !graph.is_directed() {
+            g.add_edge(b, a, 1);
+        }
+    }
+    g
+}
+
+#[test]
+fn random_directed() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let mut cycles = 0;
+    for i in 0..3000 {
+        // more negative weights as it goes
+        let g = random_graph(Graph::new(), &mut rng, -(i % 12));
+        let source = n(rng.below(g.node_count() as u64) as usize);
+        cycles += check(&g, source) as usize;
+    }
+    // both outcomes got tested
+    assert!(cycles > 100 && cycles < 2900, "{cycles} negative cycles");
+}
+
+#[test]
+fn random_undirected() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..1000 {
+        let g = random_graph(Graph::new_undirected(), &mut rng, 0);
+        let source = n(rng.below(g.node_count() as u64) as usize);
+        assert!(!check(&g, source));
+    }
+    // a negative undirected edge is a negative cycle by itself
+    let mut g = Graph::new_undirected();
+    let (a, b) = (g.add_node(()), g.add_node(()));
+    g.add_edge(a, b, -1);
+    assert!(check(&g, a));
+}
+
+#[test]
+fn unreachable_negative_cycle_is_fine() {
+    let mut g = Graph::new();
+    let nodes: Vec<NodeIndex> = (0..4).map(|_| g.add_node(())).collect();
+    g.add_edge(nodes[0], nodes[1], 5);
+    g.add_edge(nodes[2], nodes[3], -3);
+    g.add_edge(nodes[3], nodes[2], 1);
+    g.add_edge(nodes[3], nodes[1], 1);
+    let sp = bellman_ford(&g, nodes[0]).unwrap();
+    assert_eq!(sp.distance(nodes[1]), Some(5));
+    assert_eq!(sp.distance(nodes[2]), None);
+    assert!(bellman_ford(&g, nodes[2]).is_err());
+}
+
+#[test]
+fn negative_self_loop() {
+    let mut g = Graph::new();
+    let a = g.add_node(());
+    let b = g.add_node(());
+    g.add_edge(a, b, 1);
+    g.add_edge(b, b, -1);
+    assert_eq!(bellman_ford(&g, a).unwrap_err(), NegativeCycle(vec![b]));
+}
@@ -0,0 +1,144 @@
+//! Dinic's max flow: the flow is feasible and conserved, its value equals
+//! the capacity of the returned cut, and of the minimum cut found by
+//! trying every cut of small graphs.
+
+use graph::{dinic, Graph, NodeIndex};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn n(i: usize) -> NodeIndex {
+    NodeIndex::new(i)
+}
+
+fn random_graph(rng: &mut Rng, max_nodes: u64) -> Graph<(), u64> {
+    let mut graph = Graph::new();
+    let nodes = 2 + rng.below(max_nodes - 1) as usize;
+    let edges = rng.below(4 * nodes as u64) as usize;
+    for _ in 0..nodes {
+        graph.add_node(());
+    }
+    for _ in 0..edges {
+        let a = rng.below(nodes as u64) as usize;
+        let b = rng.below(nodes as u64) as usize;
+        graph.add_edge(n(a), n(b), rng.below(10));
+    }
+    graph
+}
+
+/// Capacity of edges leaving the node set `side`, a bit mask.
+fn cut_capacity(graph: &Graph<(), u64>, side: u32) -> u64 {
+    graph
+        .edge_indices()
+        .filter(|&e| {
+            let (a, b) = graph.edge_endpoints(e).unwrap();
+            side >> a.index() & 1 == 1 && side >> b.index() & 1 == 0
+        })
+        .map(|e| graph[e])
+        .sum()
+}
+
+/// Checks feasibility, conservation and the returned cut, returns the
+/// flow value.
+fn check(graph: &Graph<(), u64>, s: NodeIndex, t: NodeIndex) -> u64 {
+    let flow = dinic(graph, s, t);
+    let mut net = vec![0i64; graph.node_count()];
+    for e in graph.edge_indices() {
+        assert!(flow.flow(e) <= graph[e], "flow over capacity");
+        let (a, b) = graph.edge_endpoints(e).unwrap();
+        net[a.index()] -= flow.flow(e) as i64;
+        net[b.index()] += flow.flow(e) as i64;
+    }
+    for i in graph.node_indices() {
+        if i == s {
+            assert_eq!(net[i.index()], -(flow.value() as i64));
+        } else if i == t {
+            assert_eq!(net[i.index()], flow.value() as i64);
+        } else {
+            assert_eq!(net[i.index()], 0, "flow not conserved at {:?}", i);
+        }
+    }
+
+    assert!(flow.is_source_side(s));
+    assert!(!flow.is_source_side(t));
+    let mut cut = 0;
+    for e in graph.edge_indices() {
+        let (a, b) = graph.edge_endpoints(e).unwrap();
+        let crosses = flow.is_source_side(a) && !flow.is_source_side(b);
+        assert_eq!(flow.min_cut().contains(&e), crosses);
+        if crosses {
+            // cut edges are saturated
+            assert_eq!(flow.flow(e), graph[e]);
+            cut += graph[e];
+        } else if !flow.is_source_side(a) && flow.is_source_side(b) {
+            // and nothing flows back across the cut
+            assert_eq!(flow.flow(e), 0);
+        }
+    }
+    assert_eq!(cut, flow.value());
+    flow.value()
+}
+
+#[test]
+fn max_flow_is_min_cut() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..2000 {
+        let g = random_graph(&mut rng, 10);
+        let len = g.node_count();
+        let s = rng.below(len as u64) as usize;
+        let t = (s + 1 + rng.below(len as u64 - 1) as usize) % len;
+        let value = check(&g, n(s), n(t));
+        let min_cut = (0..1u32 << len)
+            .filter(|side| side >> s & 1 == 1 && side >> t & 1 == 0)
+            .map(|side| cut_capacity(&g, side))
+            .min()
+            .unwrap();
+        assert_eq!(value, min_cut);
+    }
+}
+
+#[test]
+fn larger_graphs_are_consistent() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..100 {
+        let g = random_graph(&mut rng, 200);
+        check(&g, n(0), n(g.node_count() - 1));
+    }
+}
+
+#[test]
+fn classic_example() {
+    // CLRS figure 26.1, max flow 23
+    let mut g = Graph::new();
+    for _ in 0..6 {
+        g.add_node(());
+    }
+    for (a, b, c) in [
+        (0, 1, 16),
+        (0, 2, 13),
+        (2, 1, 4),
+        (1, 3, 12),
+        (3, 2, 9),
+        (2, 4, 14),
+        (4, 3, 7),
+        (3, 5, 20),
+        (4, 5, 4),
+    ] {
+        g.add_edge(n(a), n(b), c);
+    }
+    assert_eq!(check(&g, n(0), n(5)), 23);
+    // no path at all
+    assert_eq!(check(&g, n(5), n(0)), 0);
+}
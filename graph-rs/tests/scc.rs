@@ -0,0 +1,171 @@
+//! Strongly connected components against naive reachability: nodes are
+//! in one component iff they reach each other, and components come in
+//! reverse topological order.
+
+use graph::{condense, tarjan_scc, Graph, NodeIndex};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn n(i: usize) -> NodeIndex {
+    NodeIndex::new(i)
+}
+
+fn random_graph(rng: &mut Rng) -> Graph<(), ()> {
+    let mut graph = Graph::new();
+    let nodes = 1 + rng.below(25) as usize;
+    let edges = rng.below(2 * nodes as u64) as usize;
+    for _ in 0..nodes {
+        graph.add_node(());
+    }
+    for _ in 0..edges {
+        let a = rng.below(nodes as u64) as usize;
+        let b = rng.below(nodes as u64) as usize;
+        graph.add_edge(n(a), n(b), ());
+    }
+    graph
+}
+
+/// `reach[a][b]` is whether there is a path from `a` to `b`.
+fn reachability<N>(graph: &Graph<N, ()>) -> Vec<Vec<bool>> {
+    let len = graph.node_count();
+    let mut reach = vec![vec![false; len]; len];
+    for a in graph.node_indices() {
+        reach[a.index()][a.index()] = true;
+        for b in graph.neighbors(a) {
+            reach[a.index()][b.index()] = true;
+        }
+    }
+    // Floyd-Warshall
+    for k in 0..len {
+        let through = reach[k].clone();
+        for row in &mut reach {
+            if row[k] {
+                for (x, &y) in row.iter_mut().zip(&through) {
+                    *x |= y;
+                }
+            }
+        }
+    }
+    reach
+}
+
+#[test]
+fn components_match_reachability() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..1000 {
+        let g = random_graph(&mut rng);
+        let reach = reachability(&g);
+        let components = tarjan_scc(&g);
+
+        let mut component_of = vec![None; g.node_count()];
+        for (i, c) in components.iter().enumerate() {
+            assert!(!c.is_empty());
+            for &m in c {
+                assert_eq!(component_of[m.index()], None, "node in two components");
+                component_of[m.index()] = Some(i);
+            }
+        }
+        let component_of: Vec<usize> = component_of.into_iter().map(Option::unwrap).collect();
+        for a in 0..g.node_count() {
+            for b in 0..g.node_count() {
+                let same = reach[a][b] && reach[b][a];
+                assert_eq!(component_of[a] == component_of[b], same);
+                // a later component never reaches an earlier one
+                if reach[a][b] {
+                    assert!(component_of[a] >= component_of[b]);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn condensation_is_reverse_topological_dag() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..1000 {
+        let g = random_graph(&mut rng);
+        let reach = reachability(&g);
+        let components = tarjan_scc(&g);
+        let dag = condense(&g);
+        assert_eq!(dag.node_count(), components.len());
+        for (i, c) in components.iter().enumerate() {
+            assert_eq!(&dag[n(i)], c);
+        }
+
+        let mut edges = Vec::new();
+        for e in dag.edge_indices() {
+            let (i, j) = dag.edge_endpoints(e).unwrap();
+            // edges go from later to earlier components
+            assert!(i > j);
+            edges.push((i, j));
+        }
+        let count = edges.len();
+        edges.sort();
+        edges.dedup();
+        assert_eq!(edges.len(), count, "parallel edges in condensation");
+
+        let dag_reach = reachability(&dag);
+        // an edge iff some node of one has an edge to some node of the other
+        for (i, ci) in components.iter().enumerate() {
+            for (j, cj) in components.iter().enumerate() {
+                let linked = i != j && ci.iter().any(|&a| g.neighbors(a).any(|b| cj.contains(&b)));
+                assert_eq!(edges.binary_search(&(n(i), n(j))).is_ok(), linked);
+                // and reachability between components is kept
+                assert_eq!(dag_reach[i][j], reach[ci[0].index()][cj[0].index()]);
+            }
+        }
+    }
+}
+
+#[test]
+fn small_examples() {
+    // 0 <-> 1 -> 2 <-> 3 -> 4, 5 alone with a loop
+    let mut g = Graph::new();
+    for _ in 0..6 {
+        g.add_node(());
+    }
+    for (a, b) in [(0, 1), (1, 0), (1, 2), (2, 3), (3, 2), (3, 4), (5, 5)] {
+        g.add_edge(n(a), n(b), ());
+    }
+    let components: Vec<Vec<usize>> = tarjan_scc(&g)
+        .into_iter()
+        .map(|c| {
+            let mut c: Vec<usize> = c.into_iter().map(NodeIndex::index).collect();
+            c.sort();
+            c
+        })
+        .collect();
+    assert_eq!(components, [vec![4], vec![2, 3], vec![0, 1], vec![5]]);
+
+    let empty = Graph::<(), ()>::new();
+    assert!(tarjan_scc(&empty).is_empty());
+    assert_eq!(condense(&empty).node_count(), 0);
+}
+
+#[test]
+fn deep_cycle() {
+    let len = 100_000;
+    let mut g = Graph::new();
+    for _ in 0..len {
+        g.add_node(());
+    }
+    for i in 0..len {
+        g.add_edge(n(i), n((i + 1) % len), ());
+    }
+    let components = tarjan_scc(&g);
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].len(), len);
+}
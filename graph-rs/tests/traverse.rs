@@ -0,0 +1,207 @@
+//! BFS and DFS: discovery order and traversal trees, on small graphs
+//! checked by hand and random graphs checked against naive versions.
+
+use graph::{Bfs, Dfs, EdgeType, Graph, NodeIndex};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn n(i: usize) -> NodeIndex {
+    NodeIndex::new(i)
+}
+
+fn random_graph<Ty: EdgeType>(mut graph: Graph<(), (), Ty>, rng: &mut Rng) -> Graph<(), (), Ty> {
+    let nodes = 1 + rng.below(30) as usize;
+    let edges = rng.below(3 * nodes as u64) as usize;
+    for _ in 0..nodes {
+        graph.add_node(());
+    }
+    for _ in 0..edges {
+        let a = rng.below(nodes as u64) as usize;
+        let b = rng.below(nodes as u64) as usize;
+        graph.add_edge(n(a), n(b), ());
+    }
+    graph
+}
+
+/// Distances in edges by repeated relaxation, no queue involved.
+fn naive_distances<Ty: EdgeType>(
+    graph: &Graph<(), (), Ty>,
+    start: NodeIndex,
+) -> Vec<Option<usize>> {
+    let mut dist = vec![None; graph.node_count()];
+    dist[start.index()] = Some(0);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for u in graph.node_indices() {
+            let Some(du) = dist[u.index()] else { continue };
+            for v in graph.neighbors(u) {
+                if dist[v.index()].is_none_or(|dv| dv > du + 1) {
+                    dist[v.index()] = Some(du + 1);
+                    changed = true;
+                }
+            }
+        }
+    }
+    dist
+}
+
+fn recursive_dfs<Ty: EdgeType>(
+    graph: &Graph<(), (), Ty>,
+    u: NodeIndex,
+    seen: &mut Vec<bool>,
+    order: &mut Vec<NodeIndex>,
+) {
+    seen[u.index()] = true;
+    order.push(u);
+    for v in graph.neighbors(u) {
+        if !seen[v.index()] {
+            recursive_dfs(graph, v, seen, order);
+        }
+    }
+}
+
+/// 0 -> 1, 0 -> 2, 1 -> 3, 2 -> 3, 3 -> 4, 2 -> 5, 5 -> 0, and 6 alone.
+fn sample() -> Graph<(), ()> {
+    let mut g = Graph::new();
+    for _ in 0..7 {
+        g.add_node(());
+    }
+    for (a, b) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (2, 5), (5, 0)] {
+        g.add_edge(n(a), n(b), ());
+    }
+    g
+}
+
+#[test]
+fn bfs_order_and_distances() {
+    let g = sample();
+    let mut bfs = Bfs::new(&g, n(0));
+    let order: Vec<usize> = bfs.by_ref().map(NodeIndex::index).collect();
+    assert_eq!(order, [0, 1, 2, 3, 5, 4]);
+    let dist: Vec<Option<usize>> = g.node_indices().map(|i| bfs.distance(i)).collect();
+    assert_eq!(
+        dist,
+        [Some(0), Some(1), Some(1), Some(2), Some(3), Some(2), None]
+    );
+    assert_eq!(bfs.parent(n(0)), None);
+    assert_eq!(bfs.parent(n(3)), Some(n(1)));
+    assert_eq!(bfs.path_to(n(4)), Some(vec![n(0), n(1), n(3), n(4)]));
+    assert_eq!(bfs.path_to(n(6)), None);
+    assert_eq!(bfs.path_to(n(0)), Some(vec![n(0)]));
+}
+
+#[test]
+fn dfs_order_and_depths() {
+    let g = sample();
+    let mut dfs = Dfs::new(&g, n(0));
+    let order: Vec<usize> = dfs.by_ref().map(NodeIndex::index).collect();
+    assert_eq!(order, [0, 1, 3, 4, 2, 5]);
+    let depth: Vec<Option<usize>> = g.node_indices().map(|i| dfs.distance(i)).collect();
+    assert_eq!(
+        depth,
+        [Some(0), Some(1), Some(1), Some(2), Some(3), Some(2), None]
+    );
+    assert_eq!(dfs.parent(n(3)), Some(n(1)));
+    assert_eq!(dfs.parent(n(5)), Some(n(2)));
+    assert_eq!(dfs.path_to(n(4)), Some(vec![n(0), n(1), n(3), n(4)]));
+    assert_eq!(dfs.path_to(n(6)), None);
+}
+
+#[test]
+fn undirected_goes_both_ways() {
+    let mut g = Graph::new_undirected();
+    for _ in 0..4 {
+        g.add_node(());
+    }
+    g.add_edge(n(1), n(0), ());
+    g.add_edge(n(2), n(1), ());
+    g.add_edge(n(3), n(3), ());
+    let order: Vec<usize> = Bfs::new(&g, n(2)).map(NodeIndex::index).collect();
+    assert_eq!(order, [2, 1, 0]);
+    let order: Vec<usize> = Dfs::new(&g, n(0)).map(NodeIndex::index).collect();
+    assert_eq!(order, [0, 1, 2]);
+    let order: Vec<usize> = Dfs::new(&g, n(3)).map(NodeIndex::index).collect();
+    assert_eq!(order, [3]);
+}
+
+fn check_random<Ty: EdgeType>(new: fn() -> Graph<(), (), Ty>) {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..500 {
+        let g = random_graph(new(), &mut rng);
+        let start = n(rng.below(g.node_count() as u64) as usize);
+        let dist = naive_distances(&g, start);
+        let reachable = dist.iter().filter(|d| d.is_some()).count();
+
+        let mut bfs = Bfs::new(&g, start);
+        let order: Vec<NodeIndex> = bfs.by_ref().collect();
+        assert_eq!(order.len(), reachable);
+        assert_eq!(order[0], start);
+        // nodes come out level by level
+        assert!(order
+            .windows(2)
+            .all(|w| dist[w[0].index()] <= dist[w[1].index()]));
+        for i in g.node_indices() {
+            assert_eq!(bfs.distance(i), dist[i.index()]);
+            if let Some(p) = bfs.parent(i) {
+                assert!(g.neighbors(p).any(|m| m == i));
+                assert_eq!(bfs.distance(p).map(|d| d + 1), bfs.distance(i));
+            }
+            if let Some(path) = bfs.path_to(i) {
+                assert_eq!(Some(path.len() - 1), dist[i.index()]);
+            }
+        }
+
+        let mut seen = vec![false; g.node_count()];
+        let mut expected = Vec::new();
+        recursive_dfs(&g, start, &mut seen, &mut expected);
+        let mut dfs = Dfs::new(&g, start);
+        let order: Vec<NodeIndex> = dfs.by_ref().collect();
+        assert_eq!(order, expected);
+        for i in g.node_indices() {
+            assert_eq!(dfs.distance(i).is_some(), seen[i.index()]);
+            if let Some(p) = dfs.parent(i) {
+                assert!(g.neighbors(p).any(|m| m == i));
+                assert_eq!(dfs.distance(p).map(|d| d + 1), dfs.distance(i));
+            }
+        }
+    }
+}
+
+#[test]
+fn random_directed() {
+    check_random(Graph::new);
+}
+
+#[test]
+fn random_undirected() {
+    check_random(Graph::new_undirected);
+}
+
+#[test]
+fn deep_path() {
+    let len = 100_000;
+    let mut g = Graph::new();
+    for _ in 0..len {
+        g.add_node(());
+    }
+    for i in 1..len {
+        g.add_edge(n(i - 1), n(i), ());
+    }
+    let mut dfs = Dfs::new(&g, n(0));
+    assert_eq!(dfs.by_ref().count(), len);
+    assert_eq!(dfs.distance(n(len - 1)), Some(len - 1));
+}
@@ -0,0 +1,120 @@
+//! Times the lock-free queues against a VecDeque behind a mutex with
+//! several producer and consumer threads.
+//!
+//! Usage: cargo bench -p lockfree [-- OPS]
+
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lockfree::{ArrayQueue, Backoff, Queue};
+
+trait MpmcQueue: Sync {
+    fn new() -> Self;
+    fn push(&self, x: u64);
+    fn pop(&self) -> Option<u64>;
+}
+
+impl MpmcQueue for Queue<u64> {
+    fn new() -> Self {
+        Queue::new()
+    }
+
+    fn push(&self, x: u64) {
+        self.enqueue(x);
+    }
+
+    fn pop(&self) -> Option<u64> {
+        self.dequeue()
+    }
+}
+
+impl MpmcQueue for ArrayQueue<u64> {
+    fn new() -> Self {
+        ArrayQueue::new(1024)
+    }
+
+    fn push(&self, mut x: u64) {
+        let backoff = Backoff::new();
+        while let Err(v) = self.try_push(x) {
+            x = v;
+            backoff.snooze();
+        }
+    }
+
+    fn pop(&self) -> Option<u64> {
+        self.try_pop()
+    }
+}
+
+impl MpmcQueue for Mutex<VecDeque<u64>> {
+    fn new() -> Self {
+        Mutex::new(VecDeque::new())
+    }
+
+    fn push(&self, x: u64) {
+        self.lock().unwrap().push_back(x);
+    }
+
+    fn pop(&self) -> Option<u64> {
+        self.lock().unwrap().pop_front()
+    }
+}
+
+const RUNS: usize = 3;
+
+/// `threads` producers each push `ops / threads` values while as many
+/// consumers pop them all.
+fn run<Q: MpmcQueue>(threads: usize, ops: usize) -> Duration {
+    let q = Q::new();
+    let per_thread = ops / threads;
+    let start = Instant::now();
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for i in 0..per_thread {
+                    q.push(i as u64);
+                }
+            });
+            s.spawn(|| {
+                let mut popped = 0;
+                let backoff = Backoff::new();
+                while popped < per_thread {
+                    match q.pop() {
+                        Some(x) => {
+                            black_box(x);
+                            popped += 1;
+                            backoff.reset();
+                        }
+                        None => backoff.snooze(),
+                    }
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+/// Best time of several runs.
+fn time<Q: MpmcQueue>(threads: usize, ops: usize) -> Duration {
+    (0..RUNS).map(|_| run::<Q>(threads, ops)).min().unwrap()
+}
+
+fn main() {
+    let ops = std::env::args()
+        .skip(1)
+        .find_map(|a| a.parse().ok())
+        .unwrap_or(1_000_000);
+    println!("passing {} u64s, best of {} runs", ops, RUNS);
+    for threads in [1, 2, 4, 8] {
+        println!("\n{} producers, {} consumers:", threads, threads);
+        let t = time::<Queue<u64>>(threads, ops);
+        println!("  {:<16} {:>10.2} ms", "lock-free", t.as_secs_f64() * 1000.0);
+        let t = time::<ArrayQueue<u64>>(threads, ops);
+        println!("  {:<16} {:>10.2} ms", "bounded", t.as_secs_f64() * 1000.0);
+        let t = time::<Mutex<VecDeque<u64>>>(threads, ops);
+        println!("  {:<16} {:>10.2} ms", "mutex VecDeque", t.as_secs_f64() * 1000.0);
+    }
+}
@@ -0,0 +1,172 @@
+//! Vyukov's bounded MPMC queue on a ring buffer.
+//!
+//! Every slot of the ring has a sequence number telling whose turn it is.
+//! A slot at position `pos` (counted without wrapping) is free for the
+//! producer at `pos` when its sequence is `pos`, and holds a value for
+//! the consumer at `pos` when its sequence is `pos + 1`. Threads claim
+//! positions by a CAS on the shared enqueue or dequeue counter, then
+//! fill or empty the slot and pass it on by bumping its sequence: to
+//! `pos + 1` for the consumer, to `pos + capacity` for the producer one
+//! lap later. Producers and consumers only meet at slots, and there is
+//! no memory to reclaim.
+//!
+//! See https://www.1024cores.net/home/lock-free-algorithms/queues/bounded-mpmc-queue
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Backoff;
+
+/// Keeps the counters on separate cache lines, so producers and
+/// consumers don't invalidate each other's.
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+struct Slot<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+pub struct ArrayQueue<T> {
+    buffer: Box<[Slot<T>]>,
+    mask: usize,
+    enqueue_pos: CachePadded<AtomicUsize>,
+    dequeue_pos: CachePadded<AtomicUsize>,
+}
+
+// SAFETY: values are moved between threads, never shared, and a slot is
+// accessed only by the thread that claimed its position
+unsafe impl<T: Send> Send for ArrayQueue<T> {}
+unsafe impl<T: Send> Sync for ArrayQueue<T> {}
+
+impl<T> ArrayQueue<T> {
+    /// Creates queue holding at least `capacity` values, rounded up to a
+    /// power of two, and to at least two. With a single slot, its sequence
+    /// when full, `pos + 1`, would be the same as when free for the next
+    /// producer at `pos + 1`.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(2).next_power_of_two();
+        let buffer = (0..capacity)
+            .map(|i| Slot {
+                seq: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+        Self {
+            buffer,
+            mask: capacity - 1,
+            enqueue_pos: CachePadded(AtomicUsize::new(0)),
+            dequeue_pos: CachePadded(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Pushes `value`, or gives it back if the queue is full.
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        let backoff = Backoff::new();
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            match (seq as isize).wrapping_sub(pos as isize) {
+                0 => match self.enqueue_pos.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: position is claimed, the slot is ours
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(p) => {
+                        pos = p;
+                        backoff.spin();
+                    }
+                },
+                // slot still holds the value from one lap ago
+                d if d < 0 => return Err(value),
+                // another producer took this position
+                _ => {
+                    backoff.spin();
+                    pos = self.enqueue_pos.load(Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Pops the oldest value, `None` if the queue is empty.
+    pub fn try_pop(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let backoff = Backoff::new();
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let seq = slot.seq.load(Ordering::Acquire);
+            match (seq as isize).wrapping_sub(pos.wrapping_add(1) as isize) {
+                0 => match self.dequeue_pos.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: position is claimed and the slot was
+                        // filled, as its sequence says
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.seq
+                            .store(pos.wrapping_add(self.mask + 1), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(p) => {
+                        pos = p;
+                        backoff.spin();
+                    }
+                },
+                // slot not filled yet
+                d if d < 0 => return None,
+                // another consumer took this position
+                _ => {
+                    backoff.spin();
+                    pos = self.dequeue_pos.load(Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Number of values, only a snapshot if other threads are active.
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.enqueue_pos.load(Ordering::SeqCst);
+            let head = self.dequeue_pos.load(Ordering::SeqCst);
+            // counters must be from the same moment
+            if self.enqueue_pos.load(Ordering::SeqCst) == tail {
+                return tail.wrapping_sub(head);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for ArrayQueue<T> {
+    fn drop(&mut self) {
+        while self.try_pop().is_some() {}
+    }
+}
@@ -0,0 +1,85 @@
+//! Exponential backoff for spin loops.
+//!
+//! Under contention, retrying a failed CAS at once only makes the cache
+//! line bounce more. Each step of the backoff spins twice as long as the
+//! one before, up to a limit. Loops waiting on another thread rather
+//! than retrying use `snooze`, which goes on to yield the thread after
+//! the spinning steps. Once the yielding steps are used up too,
+//! `is_completed` tells the caller to stop and block instead, e.g. on a
+//! futex or a parker.
+//!
+//! See https://docs.rs/crossbeam-utils/latest/crossbeam_utils/struct.Backoff.html
+
+use std::cell::Cell;
+use std::fmt;
+use std::hint;
+use std::thread;
+
+/// Steps spinning for at most 2^SPIN_LIMIT iterations.
+const SPIN_LIMIT: u32 = 6;
+/// Steps after which the caller should block.
+const YIELD_LIMIT: u32 = 10;
+
+pub struct Backoff {
+    step: Cell<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self { step: Cell::new(0) }
+    }
+
+    /// Starts over from the shortest step.
+    pub fn reset(&self) {
+        self.step.set(0);
+    }
+
+    /// Backs off after a failed attempt to change shared state, e.g. a
+    /// lost CAS race. Only spins.
+    pub fn spin(&self) {
+        let step = self.step.get();
+        for _ in 0..1 << step.min(SPIN_LIMIT) {
+            hint::spin_loop();
+        }
+        if step <= SPIN_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Backs off while waiting for another thread to make progress.
+    /// Spins at first, then yields the thread.
+    pub fn snooze(&self) {
+        let step = self.step.get();
+        if step <= SPIN_LIMIT {
+            for _ in 0..1 << step {
+                hint::spin_loop();
+            }
+        } else {
+            thread::yield_now();
+        }
+        if step <= YIELD_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Whether backing off further is pointless and the caller should
+    /// block.
+    pub fn is_completed(&self) -> bool {
+        self.step.get() > YIELD_LIMIT
+    }
+}
+
+impl fmt::Debug for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Backoff")
+            .field("step", &self.step.get())
+            .field("is_completed", &self.is_completed())
+            .finish()
+    }
+}
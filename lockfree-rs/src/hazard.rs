@@ -0,0 +1,251 @@
+//! Hazard pointers: safe memory reclamation for lock-free structures.
+//!
+//! A thread about to dereference a shared node first publishes its
+//! address in a hazard slot, then checks the node is still reachable.
+//! A removed node is retired instead of freed. Once a thread has retired
+//! enough nodes it scans all hazard slots and frees the retired nodes no
+//! slot points to; the rest wait for the next scan. A node published in
+//! a slot before it was removed is thus never freed under a reader.
+//!
+//! Slots are records in a global list that only grows, a thread takes a
+//! free record or adds a new one. Each thread keeps the records it
+//! released for reuse and its own list of retired nodes. On thread exit
+//! records are given back and nodes still protected by others are left
+//! to other threads' scans.
+//!
+//! Scans happen once the thread has retired twice as many nodes as there
+//! are records, so at least half of them get freed and the scan's cost is
+//! amortized to O(1) per node.
+//!
+//! See https://www.cs.otago.ac.nz/cosc440/readings/hazard-pointers.pdf
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+struct Record {
+    hazard: AtomicPtr<()>,
+    in_use: AtomicBool,
+    /// Records are never removed, so `next` never changes once set.
+    next: *const Record,
+}
+
+// SAFETY: `next` is only written before the record is published
+unsafe impl Sync for Record {}
+
+static RECORDS: AtomicPtr<Record> = AtomicPtr::new(ptr::null_mut());
+static RECORD_COUNT: AtomicUsize = AtomicUsize::new(0);
+/// Nodes retired by exited threads that were still protected then.
+static ORPHANS: Mutex<Vec<Retired>> = Mutex::new(Vec::new());
+
+/// Minimum number of retired nodes to trigger a scan.
+const MIN_SCAN: usize = 64;
+
+struct Retired {
+    ptr: *mut (),
+    free: unsafe fn(*mut ()),
+}
+
+// SAFETY: retired nodes are unreachable, so the thread freeing them is
+// the only one touching them, and the caller of `retire` guarantees they
+// are safe to drop on any thread
+unsafe impl Send for Retired {}
+
+unsafe fn free_box<T>(ptr: *mut ()) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+fn records() -> impl Iterator<Item = &'static Record> {
+    let mut p = RECORDS.load(Ordering::Acquire) as *const Record;
+    std::iter::from_fn(move || {
+        // SAFETY: records are never freed
+        let r = unsafe { p.as_ref()? };
+        p = r.next;
+        Some(r)
+    })
+}
+
+/// Takes a free record or adds a new one.
+fn acquire_record() -> &'static Record {
+    for r in records() {
+        if !r.in_use.load(Ordering::Relaxed)
+            && r.in_use
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        {
+            return r;
+        }
+    }
+    let r = Box::leak(Box::new(Record {
+        hazard: AtomicPtr::new(ptr::null_mut()),
+        in_use: AtomicBool::new(true),
+        next: ptr::null(),
+    }));
+    let mut head = RECORDS.load(Ordering::Relaxed);
+    loop {
+        r.next = head;
+        match RECORDS.compare_exchange_weak(head, r, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => break,
+            Err(h) => head = h,
+        }
+    }
+    RECORD_COUNT.fetch_add(1, Ordering::Relaxed);
+    r
+}
+
+fn release_record(r: &Record) {
+    r.hazard.store(ptr::null_mut(), Ordering::Release);
+    r.in_use.store(false, Ordering::Release);
+}
+
+/// Frees retired nodes not protected by any hazard pointer, keeps the
+/// rest in `retired`. Returns the number of nodes freed.
+fn scan(retired: &mut Vec<Retired>) -> usize {
+    if let Ok(mut orphans) = ORPHANS.try_lock() {
+        retired.append(&mut orphans);
+    }
+    // pairs with the fence in `protect`: a hazard published before the
+    // node was removed is seen here
+    fence(Ordering::SeqCst);
+    let hazards: HashSet<*mut ()> = records()
+        .map(|r| r.hazard.load(Ordering::Acquire))
+        .filter(|p| !p.is_null())
+        .collect();
+    let mut freed = 0;
+    retired.retain(|r| {
+        if hazards.contains(&r.ptr) {
+            return true;
+        }
+        // SAFETY: node is unreachable and no hazard points to it
+        unsafe { (r.free)(r.ptr) };
+        freed += 1;
+        false
+    });
+    freed
+}
+
+/// Thread's cached records and retired nodes.
+struct Local {
+    records: Vec<&'static Record>,
+    retired: Vec<Retired>,
+}
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        for r in self.records.drain(..) {
+            release_record(r);
+        }
+        // freed nodes may retire more, which go to the orphans now, so
+        // scan again until nothing more can be freed
+        while scan(&mut self.retired) > 0 {}
+        if !self.retired.is_empty() {
+            ORPHANS.lock().unwrap().append(&mut self.retired);
+        }
+    }
+}
+
+thread_local! {
+    static LOCAL: RefCell<Local> = const {
+        RefCell::new(Local {
+            records: Vec::new(),
+            retired: Vec::new(),
+        })
+    };
+}
+
+/// Hazard slot owned by the current thread. The slot is cleared and
+/// returned for reuse when dropped.
+pub struct HazardPointer {
+    record: &'static Record,
+}
+
+impl Default for HazardPointer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HazardPointer {
+    pub fn new() -> Self {
+        let record = LOCAL
+            .try_with(|l| l.borrow_mut().records.pop())
+            .ok()
+            .flatten()
+            .unwrap_or_else(acquire_record);
+        Self { record }
+    }
+
+    /// Loads `src` and protects the loaded pointer, retrying until `src`
+    /// still holds it after it was published. The pointee is then safe
+    /// to dereference until the hazard is changed or dropped, provided
+    /// whoever removes it from `src` retires it rather than freeing it.
+    pub fn protect<T>(&mut self, src: &AtomicPtr<T>) -> *mut T {
+        let mut p = src.load(Ordering::Relaxed);
+        loop {
+            self.set(p);
+            let q = src.load(Ordering::Acquire);
+            if p == q {
+                return p;
+            }
+            p = q;
+        }
+    }
+
+    /// Publishes `p`. The caller must check afterwards that `p` is still
+    /// reachable, only then it's protected.
+    pub fn set<T>(&mut self, p: *mut T) {
+        self.record.hazard.store(p as *mut (), Ordering::Relaxed);
+        // order the store before the caller's check, pairs with `scan`
+        fence(Ordering::SeqCst);
+    }
+
+    pub fn reset(&mut self) {
+        self.record.hazard.store(ptr::null_mut(), Ordering::Release);
+    }
+}
+
+impl Drop for HazardPointer {
+    fn drop(&mut self) {
+        self.reset();
+        let record = self.record;
+        if LOCAL.try_with(|l| l.borrow_mut().records.push(record)).is_err() {
+            release_record(record);
+        }
+    }
+}
+
+/// Frees `ptr` once no hazard pointer protects it.
+///
+/// # Safety
+///
+/// `ptr` must come from `Box::into_raw`, must be unreachable for threads
+/// that don't protect it already, and must not be retired twice. It may
+/// be freed on another thread, so `T` must be safe to drop there.
+pub unsafe fn retire<T>(ptr: *mut T) {
+    let mut node = Some(Retired {
+        ptr: ptr as *mut (),
+        free: free_box::<T>,
+    });
+    let _ = LOCAL.try_with(|l| {
+        let mut retired = {
+            let mut l = l.borrow_mut();
+            l.retired.push(node.take().unwrap());
+            let threshold = MIN_SCAN.max(2 * RECORD_COUNT.load(Ordering::Relaxed));
+            if l.retired.len() < threshold {
+                return;
+            }
+            mem::take(&mut l.retired)
+        };
+        // scan without the borrow, as freed nodes' drops may retire nodes
+        // or use hazard pointers themselves
+        scan(&mut retired);
+        l.borrow_mut().retired.append(&mut retired);
+    });
+    if let Some(node) = node {
+        // thread is exiting, leave the node to others
+        ORPHANS.lock().unwrap().push(node);
+    }
+}
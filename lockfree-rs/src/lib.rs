@@ -0,0 +1,13 @@
+//! Lock-free data structures on atomics.
+//!
+//! See https://en.wikipedia.org/wiki/Non-blocking_algorithm
+
+mod array_queue;
+mod backoff;
+mod hazard;
+mod queue;
+
+pub use array_queue::ArrayQueue;
+pub use backoff::Backoff;
+pub use hazard::{retire, HazardPointer};
+pub use queue::Queue;
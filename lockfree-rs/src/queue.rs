@@ -0,0 +1,159 @@
+//! Michael-Scott queue: unbounded lock-free MPMC queue.
+//!
+//! A linked list with atomic pointers to its first and last nodes. The
+//! first node is always a dummy whose value has been taken already, so
+//! the list is never empty and enqueuers and dequeuers work on different
+//! ends. Enqueue links a node after the last one with a CAS, then swings
+//! `tail` to it with another CAS. Dequeue swings `head` to the second
+//! node and takes its value, the second node becoming the new dummy. A
+//! thread finding `tail` lagging behind the last node helps move it on
+//! instead of waiting, so no thread can block the others.
+//!
+//! A dequeued node can't be freed right away, other threads may still
+//! be reading it. Threads protect nodes they read with hazard pointers
+//! and removed nodes are retired, to be freed once unprotected.
+//!
+//! See https://www.cs.rochester.edu/~scott/papers/1996_PODC_queues.pdf
+
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::hazard::{self, HazardPointer};
+use crate::Backoff;
+
+struct Node<T> {
+    /// Uninitialized in the dummy node.
+    value: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+    fn alloc(value: MaybeUninit<T>) -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+pub struct Queue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+}
+
+// SAFETY: values are moved between threads, never shared
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Send> Sync for Queue<T> {}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        let dummy = Node::alloc(MaybeUninit::uninit());
+        Self {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+        }
+    }
+
+    pub fn enqueue(&self, value: T) {
+        let node = Node::alloc(MaybeUninit::new(value));
+        let mut hp = HazardPointer::new();
+        let backoff = Backoff::new();
+        loop {
+            let tail = hp.protect(&self.tail);
+            // SAFETY: tail is protected
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+            if tail != self.tail.load(Ordering::Acquire) {
+                continue;
+            }
+            if !next.is_null() {
+                // tail is lagging, help move it on
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+            // SAFETY: same as above
+            let linked = unsafe {
+                (*tail)
+                    .next
+                    .compare_exchange(next, node, Ordering::Release, Ordering::Relaxed)
+                    .is_ok()
+            };
+            if linked {
+                let _ = self.tail.compare_exchange(tail, node, Ordering::Release, Ordering::Relaxed);
+                return;
+            }
+            backoff.spin();
+        }
+    }
+
+    pub fn dequeue(&self) -> Option<T> {
+        let mut hp_head = HazardPointer::new();
+        let mut hp_next = HazardPointer::new();
+        let backoff = Backoff::new();
+        loop {
+            let head = hp_head.protect(&self.head);
+            let tail = self.tail.load(Ordering::Acquire);
+            // SAFETY: head is protected
+            let next = unsafe { (*head).next.load(Ordering::Acquire) };
+            hp_next.set(next);
+            // next is reachable, and so protected, while head is still
+            // the head
+            if head != self.head.load(Ordering::Acquire) {
+                continue;
+            }
+            if next.is_null() {
+                return None;
+            }
+            if head == tail {
+                // tail is lagging behind a node being enqueued
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                // SAFETY: winning the CAS makes this thread the only one
+                // to take the value of `next`, which is now the dummy
+                let value = unsafe { (*next).value.assume_init_read() };
+                // SAFETY: head is unlinked and only this thread unlinked it
+                unsafe { hazard::retire(head) };
+                return Some(value);
+            }
+            backoff.spin();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let mut hp = HazardPointer::new();
+        let head = hp.protect(&self.head);
+        // SAFETY: head is protected
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        // SAFETY: no other thread can be inside, the first node is the
+        // dummy and the rest hold values
+        unsafe {
+            let mut node = *self.head.get_mut();
+            let mut dummy = true;
+            while !node.is_null() {
+                let mut b = Box::from_raw(node);
+                if !dummy {
+                    b.value.assume_init_drop();
+                }
+                dummy = false;
+                node = *b.next.get_mut();
+            }
+        }
+    }
+}
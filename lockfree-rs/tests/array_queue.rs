@@ -0,0 +1,146 @@
+//! Bounded queue: full and empty at the edges of capacity, every value
+//! taken exactly once under contention, values dropped exactly once.
+//! Also meant to be run under Miri.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use lockfree::ArrayQueue;
+
+const THREADS: usize = 4;
+const PER_THREAD: usize = if cfg!(miri) { 100 } else { 20_000 };
+
+#[test]
+fn capacity_rounds_up() {
+    for (asked, cap) in [(0, 2), (1, 2), (2, 2), (3, 4), (4, 4), (5, 8), (1000, 1024)] {
+        assert_eq!(ArrayQueue::<u8>::new(asked).capacity(), cap);
+    }
+}
+
+#[test]
+fn capacity_one() {
+    // gets the smallest ring, of two slots
+    let q = ArrayQueue::new(1);
+    assert!(q.is_empty());
+    assert_eq!(q.try_pop(), None);
+    // many laps around the ring, one value at a time and full
+    for i in 0..100 {
+        assert_eq!(q.try_push(i), Ok(()));
+        assert_eq!(q.len(), 1);
+        assert_eq!(q.try_pop(), Some(i));
+        assert_eq!(q.try_pop(), None);
+        assert!(q.is_empty());
+        assert_eq!(q.try_push(2 * i), Ok(()));
+        assert_eq!(q.try_push(2 * i + 1), Ok(()));
+        assert_eq!(q.try_push(-1), Err(-1));
+        assert_eq!(q.len(), 2);
+        assert_eq!(q.try_pop(), Some(2 * i));
+        assert_eq!(q.try_pop(), Some(2 * i + 1));
+        assert_eq!(q.try_pop(), None);
+    }
+}
+
+#[test]
+fn full_and_empty() {
+    for cap in [2, 4, 8, 64] {
+        let q = ArrayQueue::new(cap);
+        for lap in 0..3 {
+            for i in 0..cap {
+                assert_eq!(q.try_push(lap * cap + i), Ok(()));
+                assert_eq!(q.len(), i + 1);
+            }
+            assert_eq!(q.try_push(usize::MAX), Err(usize::MAX));
+            // half out, half back in, wrapping around the ring
+            for i in 0..cap / 2 {
+                assert_eq!(q.try_pop(), Some(lap * cap + i));
+            }
+            for i in 0..cap / 2 {
+                assert_eq!(q.try_push(1000 + i), Ok(()));
+            }
+            assert!(q.try_push(usize::MAX).is_err());
+            for i in cap / 2..cap {
+                assert_eq!(q.try_pop(), Some(lap * cap + i));
+            }
+            for i in 0..cap / 2 {
+                assert_eq!(q.try_pop(), Some(1000 + i));
+            }
+            assert_eq!(q.try_pop(), None);
+            assert_eq!(q.len(), 0);
+        }
+    }
+}
+
+#[test]
+fn mpmc_each_value_once() {
+    for cap in [2, 16] {
+        let q = ArrayQueue::new(cap);
+        let taken: Vec<Vec<usize>> = thread::scope(|s| {
+            for p in 0..THREADS {
+                let q = &q;
+                s.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        let mut x = p * PER_THREAD + i;
+                        while let Err(back) = q.try_push(x) {
+                            x = back;
+                            thread::yield_now();
+                        }
+                    }
+                });
+            }
+            let consumers: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    let q = &q;
+                    s.spawn(move || {
+                        let mut got = Vec::new();
+                        let mut last = [None; THREADS];
+                        while got.len() < PER_THREAD {
+                            match q.try_pop() {
+                                Some(x) => {
+                                    let p = x / PER_THREAD;
+                                    assert!(last[p] < Some(x), "out of order");
+                                    last[p] = Some(x);
+                                    got.push(x);
+                                }
+                                None => thread::yield_now(),
+                            }
+                        }
+                        got
+                    })
+                })
+                .collect();
+            consumers.into_iter().map(|c| c.join().unwrap()).collect()
+        });
+        let mut all: Vec<usize> = taken.into_iter().flatten().collect();
+        all.sort_unstable();
+        assert!(all.into_iter().eq(0..THREADS * PER_THREAD));
+        assert!(q.is_empty());
+    }
+}
+
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn values_dropped_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let q = ArrayQueue::new(8);
+    for _ in 0..8 {
+        assert!(q.try_push(Counted(drops.clone())).is_ok());
+    }
+    // a rejected value is given back, not dropped
+    let back = q.try_push(Counted(drops.clone())).unwrap_err();
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(back);
+    for _ in 0..3 {
+        drop(q.try_pop());
+    }
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+    drop(q);
+    assert_eq!(drops.load(Ordering::Relaxed), 9);
+}
@@ -0,0 +1,29 @@
+//! Backoff steps: spinning alone never completes, snoozing does.
+
+use lockfree::Backoff;
+
+#[test]
+fn spin_never_completes() {
+    let b = Backoff::new();
+    for _ in 0..100 {
+        b.spin();
+        assert!(!b.is_completed());
+    }
+}
+
+#[test]
+fn snooze_completes() {
+    let b = Backoff::new();
+    let mut steps = 0;
+    while !b.is_completed() {
+        b.snooze();
+        steps += 1;
+        assert!(steps <= 100, "snoozing never completed");
+    }
+    assert!(steps > 7, "completed while still spinning");
+    b.snooze();
+    assert!(b.is_completed());
+    b.reset();
+    assert!(!b.is_completed());
+    assert_eq!(format!("{:?}", b), "Backoff { step: 0, is_completed: false }");
+}
@@ -0,0 +1,113 @@
+//! Retired nodes are freed once unprotected, and only then. Also meant to
+//! be run under Miri: `cargo +nightly miri test -p lockfree`.
+
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lockfree::{retire, HazardPointer};
+
+/// Enough retires to force a scan.
+const MANY: usize = if cfg!(miri) { 200 } else { 1000 };
+
+struct Counted(&'static AtomicUsize);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn retire_counted(drops: &'static AtomicUsize) {
+    // SAFETY: fresh box, never shared
+    unsafe { retire(Box::into_raw(Box::new(Counted(drops)))) };
+}
+
+/// Waits for `drops` to reach `n`. Other tests' threads scanning at the
+/// same time may have taken the nodes and be about to free them.
+fn wait_drops(drops: &AtomicUsize, n: usize) {
+    let start = Instant::now();
+    while drops.load(Ordering::Relaxed) < n {
+        assert!(start.elapsed() < Duration::from_secs(10), "nodes not freed");
+        thread::yield_now();
+    }
+    assert_eq!(drops.load(Ordering::Relaxed), n);
+}
+
+#[test]
+fn retired_freed_by_thread_exit() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    thread::spawn(|| {
+        for _ in 0..MANY {
+            retire_counted(&DROPS);
+        }
+    })
+    .join()
+    .unwrap();
+    wait_drops(&DROPS, MANY);
+}
+
+#[test]
+fn protected_not_freed() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    static OTHERS: AtomicUsize = AtomicUsize::new(0);
+    let node = Box::into_raw(Box::new(Counted(&DROPS)));
+    let src = AtomicPtr::new(node);
+    let mut hp = HazardPointer::new();
+    assert_eq!(hp.protect(&src), node);
+    src.store(ptr::null_mut(), Ordering::Release);
+    // SAFETY: unreachable from `src` now, only protected by `hp`
+    unsafe { retire(node) };
+    thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..MANY {
+                retire_counted(&OTHERS);
+            }
+        });
+    });
+    for _ in 0..MANY {
+        retire_counted(&OTHERS);
+    }
+    assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+    // SAFETY: still protected
+    assert!(ptr::eq(unsafe { (*node).0 }, &DROPS));
+    drop(hp);
+    thread::spawn(|| {
+        for _ in 0..MANY {
+            retire_counted(&OTHERS);
+        }
+    })
+    .join()
+    .unwrap();
+    // the node went to the orphans or stayed with this thread, either way
+    // the next scans free it
+    for _ in 0..MANY {
+        retire_counted(&OTHERS);
+    }
+    wait_drops(&DROPS, 1);
+}
+
+/// Node whose drop retires another node and uses a hazard pointer.
+struct Reentrant(&'static AtomicUsize);
+
+impl Drop for Reentrant {
+    fn drop(&mut self) {
+        let _hp = HazardPointer::new();
+        retire_counted(self.0);
+    }
+}
+
+#[test]
+fn drop_of_retired_may_retire() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+    thread::spawn(|| {
+        for _ in 0..MANY {
+            // SAFETY: fresh box, never shared
+            unsafe { retire(Box::into_raw(Box::new(Reentrant(&DROPS)))) };
+        }
+    })
+    .join()
+    .unwrap();
+    wait_drops(&DROPS, MANY);
+}
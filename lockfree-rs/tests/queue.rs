@@ -0,0 +1,120 @@
+//! Michael-Scott queue: FIFO order, every value taken exactly once under
+//! contention, and values dropped exactly once. Also meant to be run
+//! under Miri, whose leak check covers the retired nodes.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use lockfree::Queue;
+
+const THREADS: usize = 4;
+const PER_THREAD: usize = if cfg!(miri) { 100 } else { 20_000 };
+
+#[test]
+fn fifo() {
+    let q = Queue::new();
+    assert!(q.is_empty());
+    assert_eq!(q.dequeue(), None::<i32>);
+    for i in 0..100 {
+        q.enqueue(i);
+    }
+    assert!(!q.is_empty());
+    for i in 0..50 {
+        assert_eq!(q.dequeue(), Some(i));
+    }
+    for i in 100..150 {
+        q.enqueue(i);
+    }
+    for i in 50..150 {
+        assert_eq!(q.dequeue(), Some(i));
+    }
+    assert_eq!(q.dequeue(), None);
+    assert!(q.is_empty());
+}
+
+#[test]
+fn mpmc_each_value_once() {
+    let q = Queue::new();
+    let taken: Vec<Vec<usize>> = thread::scope(|s| {
+        for p in 0..THREADS {
+            let q = &q;
+            s.spawn(move || {
+                for i in 0..PER_THREAD {
+                    q.enqueue(p * PER_THREAD + i);
+                }
+            });
+        }
+        let consumers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let q = &q;
+                s.spawn(move || {
+                    let mut got = Vec::new();
+                    // per producer, values must come out in order
+                    let mut last = [None; THREADS];
+                    while got.len() < PER_THREAD {
+                        match q.dequeue() {
+                            Some(x) => {
+                                let p = x / PER_THREAD;
+                                assert!(last[p] < Some(x), "out of order");
+                                last[p] = Some(x);
+                                got.push(x);
+                            }
+                            None => thread::yield_now(),
+                        }
+                    }
+                    got
+                })
+            })
+            .collect();
+        consumers.into_iter().map(|c| c.join().unwrap()).collect()
+    });
+    let mut all: Vec<usize> = taken.into_iter().flatten().collect();
+    all.sort_unstable();
+    assert!(all.into_iter().eq(0..THREADS * PER_THREAD));
+    assert!(q.is_empty());
+}
+
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn values_dropped_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let q = Queue::new();
+    for _ in 0..10 {
+        q.enqueue(Counted(drops.clone()));
+    }
+    for _ in 0..4 {
+        drop(q.dequeue());
+    }
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+    // pending values are dropped with the queue, the dummy isn't
+    drop(q);
+    assert_eq!(drops.load(Ordering::Relaxed), 10);
+}
+
+#[test]
+fn dropped_with_values_across_threads() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let q = Queue::new();
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                for i in 0..PER_THREAD {
+                    q.enqueue(Counted(drops.clone()));
+                    if i % 3 == 0 {
+                        drop(q.dequeue());
+                    }
+                }
+            });
+        }
+    });
+    drop(q);
+    assert_eq!(drops.load(Ordering::Relaxed), THREADS * PER_THREAD);
+}
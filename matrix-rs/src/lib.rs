@@ -0,0 +1,191 @@
+//! Dense matrices over a semiring.
+//!
+//! Elements are stored row by row in one vector. Multiplication is the
+//! naive triple loop for small matrices and the same loop over tiles for
+//! large ones, so that the tiles being worked on stay in cache. For
+//! element types with subtraction, `mul_strassen` multiplies large
+//! matrices by Strassen's algorithm in O(n^2.81). Powers of square
+//! matrices are computed by repeated squaring in O(log n)
+//! multiplications, e.g. walks of length k in a graph from its adjacency
+//! matrix, or the k-th term of a linear recurrence from its companion
+//! matrix.
+//!
+//! See https://en.wikipedia.org/wiki/Matrix_multiplication_algorithm
+
+use std::fmt;
+use std::ops::{Index, IndexMut, Mul};
+
+mod mul;
+mod semiring;
+
+pub use semiring::{MinPlus, Ring, Semiring};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    /// Row-major.
+    data: Vec<T>,
+}
+
+impl<T: Semiring> Matrix<T> {
+    /// Creates a matrix of zeros.
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+
+    /// Matrix product. Panics if `self.cols() != other.rows()`.
+    ///
+    /// This is always the blocked loop, since a semiring has no
+    /// subtraction. For rings, [`Matrix::mul_strassen`] is faster on
+    /// large matrices.
+    pub fn mul(&self, other: &Self) -> Self {
+        self.check_mul(other);
+        let mut c = Self::zeros(self.rows, other.cols);
+        mul::blocked(self, other, &mut c);
+        c
+    }
+
+    /// Matrix product by Strassen's algorithm, or by the blocked loop
+    /// if any side is at most 64, where Strassen doesn't pay off. Panics
+    /// if `self.cols() != other.rows()`.
+    pub fn mul_strassen(&self, other: &Self) -> Self
+    where
+        T: Ring,
+    {
+        self.check_mul(other);
+        mul::strassen(self, other)
+    }
+
+    fn check_mul(&self, other: &Self) {
+        assert!(
+            self.cols == other.rows,
+            "can't multiply {}x{} matrix by {}x{} matrix",
+            self.rows,
+            self.cols,
+            other.rows,
+            other.cols
+        );
+    }
+
+    /// `self` to the power of `exp`, by repeated squaring. Panics if the
+    /// matrix is not square.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        assert!(
+            self.rows == self.cols,
+            "power of non-square {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        let mut base = self.clone();
+        let mut acc = Self::identity(self.rows);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        acc
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Creates a matrix from elements row by row.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        assert_eq!(data.len(), rows * cols, "wrong number of elements");
+        Self { rows, cols, data }
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let data = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Self { rows, cols, data }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        assert!(i < self.rows, "row {} out of range for {} rows", i, self.rows);
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(self.cols, self.rows, |i, j| self[(j, i)].clone())
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(
+            i < self.rows && j < self.cols,
+            "index ({}, {}) out of range for {}x{} matrix",
+            i,
+            j,
+            self.rows,
+            self.cols
+        );
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(
+            i < self.rows && j < self.cols,
+            "index ({}, {}) out of range for {}x{} matrix",
+            i,
+            j,
+            self.rows,
+            self.cols
+        );
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<T: Semiring> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        Matrix::mul(self, other)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Matrix<T> {
+    /// One row per line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = f.debug_list();
+        for i in 0..self.rows {
+            rows.entry(&self.row(i));
+        }
+        rows.finish()
+    }
+}
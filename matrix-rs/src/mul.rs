@@ -0,0 +1,123 @@
+//! Multiplication algorithms.
+
+use crate::{Matrix, Ring, Semiring};
+
+/// Side of the square tiles of the blocked multiplication.
+const BLOCK: usize = 64;
+/// Side up to which Strassen falls back to the plain loop.
+const STRASSEN_LEAF: usize = 64;
+
+/// Adds `a * b` to `c`, tile by tile. The innermost loop goes along rows
+/// of `b` and `c`, which are contiguous. Matrices that fit in one tile
+/// are just the naive i-k-j loop.
+pub fn blocked<T: Semiring>(a: &Matrix<T>, b: &Matrix<T>, c: &mut Matrix<T>) {
+    let (n, m, p) = (a.rows, a.cols, b.cols);
+    for i0 in (0..n).step_by(BLOCK) {
+        for k0 in (0..m).step_by(BLOCK) {
+            for j0 in (0..p).step_by(BLOCK) {
+                for i in i0..(i0 + BLOCK).min(n) {
+                    for k in k0..(k0 + BLOCK).min(m) {
+                        let x = &a.data[i * m + k];
+                        let b_row = &b.data[k * p..(k + 1) * p];
+                        let c_row = &mut c.data[i * p..(i + 1) * p];
+                        for j in j0..(j0 + BLOCK).min(p) {
+                            c_row[j] = T::add(&c_row[j], &T::mul(x, &b_row[j]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `a * b`, padding both to a square with a power of two side first.
+/// If any side is at most `STRASSEN_LEAF`, the padding would cost more
+/// than Strassen saves, and the blocked loop is used instead.
+pub fn strassen<T: Ring>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    if a.rows.min(a.cols).min(b.cols) <= STRASSEN_LEAF {
+        let mut c = Matrix::zeros(a.rows, b.cols);
+        blocked(a, b, &mut c);
+        return c;
+    }
+    let n = a.rows.max(a.cols).max(b.cols).next_power_of_two();
+    let c = strassen_square(&pad(a, n), &pad(b, n));
+    Matrix::from_fn(a.rows, b.cols, |i, j| c[(i, j)].clone())
+}
+
+fn pad<T: Semiring>(m: &Matrix<T>, n: usize) -> Matrix<T> {
+    if m.rows == n && m.cols == n {
+        return m.clone();
+    }
+    Matrix::from_fn(n, n, |i, j| {
+        if i < m.rows && j < m.cols {
+            m[(i, j)].clone()
+        } else {
+            T::zero()
+        }
+    })
+}
+
+/// Quadrant `(qi, qj)` of a matrix with an even side.
+fn quadrant<T: Clone>(m: &Matrix<T>, qi: usize, qj: usize) -> Matrix<T> {
+    let h = m.rows / 2;
+    Matrix::from_fn(h, h, |i, j| m[(qi * h + i, qj * h + j)].clone())
+}
+
+type Op<T> = fn(&T, &T) -> T;
+
+fn zip<T: Clone>(a: &Matrix<T>, b: &Matrix<T>, f: Op<T>) -> Matrix<T> {
+    Matrix::from_fn(a.rows, a.cols, |i, j| f(&a[(i, j)], &b[(i, j)]))
+}
+
+/// Product of square matrices with a power of two side, from seven
+/// products of quadrants instead of eight:
+///
+/// m1 = (a11 + a22)(b11 + b22)    c11 = m1 + m4 - m5 + m7
+/// m2 = (a21 + a22) b11           c12 = m3 + m5
+/// m3 = a11 (b12 - b22)           c21 = m2 + m4
+/// m4 = a22 (b21 - b11)           c22 = m1 - m2 + m3 + m6
+/// m5 = (a11 + a12) b22
+/// m6 = (a21 - a11)(b11 + b12)
+/// m7 = (a12 - a22)(b21 + b22)
+fn strassen_square<T: Ring>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    let n = a.rows;
+    if n <= STRASSEN_LEAF {
+        let mut c = Matrix::zeros(n, n);
+        blocked(a, b, &mut c);
+        return c;
+    }
+    let (add, sub): (Op<T>, Op<T>) = (T::add, T::sub);
+    let (a11, a12, a21, a22) = (
+        quadrant(a, 0, 0),
+        quadrant(a, 0, 1),
+        quadrant(a, 1, 0),
+        quadrant(a, 1, 1),
+    );
+    let (b11, b12, b21, b22) = (
+        quadrant(b, 0, 0),
+        quadrant(b, 0, 1),
+        quadrant(b, 1, 0),
+        quadrant(b, 1, 1),
+    );
+    let m1 = strassen_square(&zip(&a11, &a22, add), &zip(&b11, &b22, add));
+    let m2 = strassen_square(&zip(&a21, &a22, add), &b11);
+    let m3 = strassen_square(&a11, &zip(&b12, &b22, sub));
+    let m4 = strassen_square(&a22, &zip(&b21, &b11, sub));
+    let m5 = strassen_square(&zip(&a11, &a12, add), &b22);
+    let m6 = strassen_square(&zip(&a21, &a11, sub), &zip(&b11, &b12, add));
+    let m7 = strassen_square(&zip(&a12, &a22, sub), &zip(&b21, &b22, add));
+    let c11 = zip(&zip(&m1, &m4, add), &zip(&m7, &m5, sub), add);
+    let c12 = zip(&m3, &m5, add);
+    let c21 = zip(&m2, &m4, add);
+    let c22 = zip(&zip(&m1, &m2, sub), &zip(&m3, &m6, add), add);
+    let h = n / 2;
+    Matrix::from_fn(n, n, |i, j| {
+        let q = match (i < h, j < h) {
+            (true, true) => &c11,
+            (true, false) => &c12,
+            (false, true) => &c21,
+            (false, false) => &c22,
+        };
+        q[(i % h, j % h)].clone()
+    })
+}
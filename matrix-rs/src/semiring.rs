@@ -0,0 +1,194 @@
+//! Element types of matrices.
+//!
+//! Matrix multiplication only needs addition and multiplication with
+//! their identities, where multiplication distributes over addition and
+//! zero annihilates: a semiring. Besides numbers, this covers booleans
+//! with or/and, whose matrix powers tell reachability in a graph, and
+//! min-plus numbers, whose powers give shortest paths of bounded length.
+
+use std::num::Wrapping;
+
+pub trait Semiring: Clone {
+    /// Identity of `add`, and annihilator of `mul`.
+    fn zero() -> Self;
+    /// Identity of `mul`.
+    fn one() -> Self;
+    fn add(a: &Self, b: &Self) -> Self;
+    fn mul(a: &Self, b: &Self) -> Self;
+}
+
+/// Semiring with subtraction, needed by Strassen's multiplication.
+pub trait Ring: Semiring {
+    fn sub(a: &Self, b: &Self) -> Self;
+}
+
+macro_rules! impl_semiring {
+    ($($t:ty),*) => {$(
+        impl Semiring for $t {
+            fn zero() -> Self {
+                0 as $t
+            }
+
+            fn one() -> Self {
+                1 as $t
+            }
+
+            fn add(a: &Self, b: &Self) -> Self {
+                *a + *b
+            }
+
+            fn mul(a: &Self, b: &Self) -> Self {
+                *a * *b
+            }
+        }
+
+        /// Overflowing arithmetic wraps around, e.g. for results modulo
+        /// 2^64.
+        impl Semiring for Wrapping<$t> {
+            fn zero() -> Self {
+                Wrapping(0 as $t)
+            }
+
+            fn one() -> Self {
+                Wrapping(1 as $t)
+            }
+
+            fn add(a: &Self, b: &Self) -> Self {
+                *a + *b
+            }
+
+            fn mul(a: &Self, b: &Self) -> Self {
+                *a * *b
+            }
+        }
+
+        impl Ring for Wrapping<$t> {
+            fn sub(a: &Self, b: &Self) -> Self {
+                *a - *b
+            }
+        }
+    )*};
+}
+
+impl_semiring!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_ring {
+    ($($t:ty),*) => {$(
+        impl Ring for $t {
+            fn sub(a: &Self, b: &Self) -> Self {
+                *a - *b
+            }
+        }
+    )*};
+}
+
+// unsigned integers aren't rings, differences could go below zero
+impl_ring!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_semiring_float {
+    ($($t:ty),*) => {$(
+        impl Semiring for $t {
+            fn zero() -> Self {
+                0.0
+            }
+
+            fn one() -> Self {
+                1.0
+            }
+
+            fn add(a: &Self, b: &Self) -> Self {
+                a + b
+            }
+
+            fn mul(a: &Self, b: &Self) -> Self {
+                a * b
+            }
+        }
+
+        impl Ring for $t {
+            fn sub(a: &Self, b: &Self) -> Self {
+                a - b
+            }
+        }
+    )*};
+}
+
+impl_semiring_float!(f32, f64);
+
+/// Or and and.
+impl Semiring for bool {
+    fn zero() -> Self {
+        false
+    }
+
+    fn one() -> Self {
+        true
+    }
+
+    fn add(a: &Self, b: &Self) -> Self {
+        *a || *b
+    }
+
+    fn mul(a: &Self, b: &Self) -> Self {
+        *a && *b
+    }
+}
+
+/// Number with min as addition and + as multiplication. Zero is the
+/// type's max value, standing for infinity, e.g. no path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MinPlus<T>(pub T);
+
+macro_rules! impl_min_plus {
+    ($($t:ty),*) => {$(
+        impl Semiring for MinPlus<$t> {
+            fn zero() -> Self {
+                MinPlus(<$t>::MAX)
+            }
+
+            fn one() -> Self {
+                MinPlus(0 as $t)
+            }
+
+            fn add(a: &Self, b: &Self) -> Self {
+                if a.0 <= b.0 { *a } else { *b }
+            }
+
+            fn mul(a: &Self, b: &Self) -> Self {
+                // infinity plus anything stays infinity, and so does a
+                // sum too large to represent
+                if a.0 == <$t>::MAX || b.0 == <$t>::MAX {
+                    Self::zero()
+                } else {
+                    a.0.checked_add(b.0).map_or(Self::zero(), MinPlus)
+                }
+            }
+        }
+    )*};
+}
+
+impl_min_plus!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_min_plus_float {
+    ($($t:ty),*) => {$(
+        impl Semiring for MinPlus<$t> {
+            fn zero() -> Self {
+                MinPlus(<$t>::INFINITY)
+            }
+
+            fn one() -> Self {
+                MinPlus(0.0)
+            }
+
+            fn add(a: &Self, b: &Self) -> Self {
+                MinPlus(a.0.min(b.0))
+            }
+
+            fn mul(a: &Self, b: &Self) -> Self {
+                MinPlus(a.0 + b.0)
+            }
+        }
+    )*};
+}
+
+impl_min_plus_float!(f32, f64);
@@ -0,0 +1,120 @@
+//! Blocked and Strassen products against the naive triple loop, on
+//! shapes that are neither square nor powers of two, so Strassen pads
+//! and tiles are partial. Powers against repeated multiplication.
+
+use std::num::Wrapping;
+
+use matrix::{Matrix, MinPlus, Semiring};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn random(rng: &mut Rng, rows: usize, cols: usize) -> Matrix<i64> {
+    Matrix::from_fn(rows, cols, |_, _| rng.below(201) as i64 - 100)
+}
+
+fn naive<T: Semiring>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    Matrix::from_fn(a.rows(), b.cols(), |i, j| {
+        (0..a.cols()).fold(T::zero(), |acc, k| {
+            T::add(&acc, &T::mul(&a[(i, k)], &b[(k, j)]))
+        })
+    })
+}
+
+/// (rows of a, cols of a, cols of b), around leaf and tile sides of 64.
+const SHAPES: &[(usize, usize, usize)] = &[
+    (0, 0, 0),
+    (0, 3, 2),
+    (2, 0, 3),
+    (1, 1, 1),
+    (3, 5, 7),
+    (7, 5, 3),
+    (1, 100, 1),
+    (100, 1, 100),
+    (63, 65, 64),
+    (65, 65, 65),
+    (100, 70, 130),
+    (129, 3, 200),
+];
+
+#[test]
+fn strassen_matches_naive() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for &(n, m, p) in SHAPES {
+        let (a, b) = (random(&mut rng, n, m), random(&mut rng, m, p));
+        let expected = naive(&a, &b);
+        assert_eq!(a.mul_strassen(&b), expected, "{}x{} by {}x{}", n, m, m, p);
+        assert_eq!(a.mul(&b), expected, "{}x{} by {}x{}", n, m, m, p);
+        assert_eq!(&a * &b, expected);
+    }
+}
+
+#[test]
+fn strassen_wrapping_and_floats() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    let (n, m, p) = (70, 90, 66);
+    // wrapping products overflow all the time, still exact mod 2^64
+    let a = Matrix::from_fn(n, m, |_, _| Wrapping(rng.next() as i64));
+    let b = Matrix::from_fn(m, p, |_, _| Wrapping(rng.next() as i64));
+    assert_eq!(a.mul_strassen(&b), naive(&a, &b));
+    // small integers as floats are exact too
+    let a = Matrix::from_fn(n, m, |_, _| rng.below(10) as f64);
+    let b = Matrix::from_fn(m, p, |_, _| rng.below(10) as f64);
+    assert_eq!(a.mul_strassen(&b), naive(&a, &b));
+}
+
+#[test]
+fn powers() {
+    let mut rng = Rng(7);
+    let a = Matrix::from_fn(5, 5, |_, _| Wrapping(rng.next()));
+    let mut acc = Matrix::identity(5);
+    for exp in 0..20 {
+        assert_eq!(a.pow(exp), acc, "power {}", exp);
+        acc = naive(&acc, &a);
+    }
+    // fibonacci from the companion matrix
+    let fib = Matrix::from_vec(2, 2, vec![1u64, 1, 1, 0]);
+    assert_eq!(fib.pow(90)[(0, 1)], 2880067194370816120);
+}
+
+#[test]
+fn min_plus_shortest_paths() {
+    let inf = MinPlus::<u32>::zero();
+    // 0 -> 1 -> 2 -> 3, and a long edge 0 -> 3
+    let mut g = Matrix::from_fn(4, 4, |i, j| if i == j { MinPlus(0) } else { inf });
+    g[(0, 1)] = MinPlus(1);
+    g[(1, 2)] = MinPlus(2);
+    g[(2, 3)] = MinPlus(3);
+    g[(0, 3)] = MinPlus(10);
+    let d = g.pow(3);
+    assert_eq!(d[(0, 3)], MinPlus(6));
+    assert_eq!(d[(0, 2)], MinPlus(3));
+    assert_eq!(d[(3, 0)], inf);
+    assert_eq!(g.pow(1)[(0, 3)], MinPlus(10));
+    // sums past the max are infinite, not wrapped or panicking
+    assert_eq!(
+        MinPlus::mul(&MinPlus(200u8), &MinPlus(100)),
+        MinPlus::zero()
+    );
+    assert_eq!(MinPlus::mul(&MinPlus(200u8), &MinPlus(55)), MinPlus::zero());
+    assert_eq!(MinPlus::mul(&MinPlus(200u8), &MinPlus(54)), MinPlus(254));
+}
+
+#[test]
+#[should_panic(expected = "can't multiply 2x3 matrix by 2x3 matrix")]
+fn shapes_differ() {
+    let a = Matrix::<i64>::zeros(2, 3);
+    a.mul_strassen(&a);
+}
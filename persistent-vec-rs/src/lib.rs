@@ -0,0 +1,380 @@
+//! Persistent vector: a vector whose clones share structure.
+//!
+//! Items are stored in a tree with up to 32 children per node and up to
+//! 32 items per leaf, so its depth is O(log32 n). Nodes are reference
+//! counted and never changed while shared: an edit copies the path from
+//! the root to the edited leaf and shares everything else with the old
+//! version. Cloning the vector is O(1).
+//!
+//! The tree is a relaxed radix balanced (RRB) tree. In a node where all
+//! children but the last are full, the child holding an index is found
+//! from the index bits alone. Concatenation can leave nodes that are not
+//! full in the middle of the tree, such nodes keep cumulative sizes of
+//! their children which are searched instead. Concatenation joins two
+//! trees along the seam between them, repacking nodes there if they got
+//! too sparse, in O(log n) node copies.
+//!
+//! See https://infoscience.epfl.ch/record/169879/files/RMTrees.pdf
+
+use std::fmt;
+use std::ops::Index;
+use std::slice;
+use std::sync::Arc;
+
+const BITS: u32 = 5;
+const WIDTH: usize = 1 << BITS;
+/// Nodes along the concatenation seam are repacked when there are more
+/// than this many of them over the minimum needed to hold their slots.
+const EXTRA: usize = 2;
+
+#[derive(Clone)]
+enum Node<T> {
+    Leaf(Vec<T>),
+    Branch(Branch<T>),
+}
+
+#[derive(Clone)]
+struct Branch<T> {
+    children: Vec<Arc<Node<T>>>,
+    /// Cumulative sizes of children, `None` if all children but the last
+    /// are full.
+    sizes: Option<Vec<usize>>,
+    len: usize,
+}
+
+impl<T> Node<T> {
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf(items) => items.len(),
+            Node::Branch(b) => b.len,
+        }
+    }
+
+    /// Number of items in a leaf or children in a branch.
+    fn slots(&self) -> usize {
+        match self {
+            Node::Leaf(items) => items.len(),
+            Node::Branch(b) => b.children.len(),
+        }
+    }
+}
+
+impl<T> Branch<T> {
+    /// Branch at `height` above leaves, which are at height 0.
+    fn new(children: Vec<Arc<Node<T>>>, height: u32) -> Self {
+        let mut b = Self {
+            children,
+            sizes: None,
+            len: 0,
+        };
+        b.update(height);
+        b
+    }
+
+    /// Recomputes length and sizes after children changed.
+    fn update(&mut self, height: u32) {
+        let full = 1 << (BITS * height);
+        let n = self.children.len();
+        self.len = self.children.iter().map(|c| c.len()).sum();
+        self.sizes = if self.children[..n - 1].iter().all(|c| c.len() == full) {
+            None
+        } else {
+            let mut total = 0;
+            Some(self.children.iter().map(|c| {
+                total += c.len();
+                total
+            }).collect())
+        };
+    }
+
+    /// Child holding index `i` and index of the child's first item.
+    fn locate(&self, i: usize, height: u32) -> (usize, usize) {
+        match &self.sizes {
+            Some(sizes) => {
+                let k = sizes.partition_point(|&s| s <= i);
+                (k, if k == 0 { 0 } else { sizes[k - 1] })
+            }
+            None => {
+                let k = i >> (BITS * height);
+                (k, k << (BITS * height))
+            }
+        }
+    }
+}
+
+pub struct PersistentVec<T> {
+    root: Arc<Node<T>>,
+    height: u32,
+}
+
+impl<T> Clone for PersistentVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Default for PersistentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PersistentVec<T> {
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(Node::Leaf(Vec::new())),
+            height: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len() {
+            return None;
+        }
+        let mut node = &*self.root;
+        let mut i = i;
+        let mut height = self.height;
+        loop {
+            match node {
+                Node::Leaf(items) => return Some(&items[i]),
+                Node::Branch(b) => {
+                    let (k, start) = b.locate(i, height);
+                    node = &b.children[k];
+                    i -= start;
+                    height -= 1;
+                }
+            }
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            stack: vec![slice::from_ref(&self.root).iter()],
+            leaf: [].iter(),
+        }
+    }
+}
+
+impl<T: Clone> PersistentVec<T> {
+    /// Replaces item at `i`, copying the path to it if it's shared.
+    pub fn set(&mut self, i: usize, x: T) {
+        assert!(i < self.len(), "index {} out of range for length {}", i, self.len());
+        set(&mut self.root, self.height, i, x);
+    }
+
+    pub fn push(&mut self, x: T) {
+        if let Some(sibling) = push(&mut self.root, self.height, x) {
+            self.height += 1;
+            let children = vec![self.root.clone(), sibling];
+            self.root = Arc::new(Node::Branch(Branch::new(children, self.height)));
+        }
+    }
+
+    /// Appends items of `other` after items of `self`. Both vectors keep
+    /// sharing the nodes away from the seam.
+    pub fn append(&mut self, other: &Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = other.clone();
+            return;
+        }
+        let height = self.height.max(other.height);
+        let left = raise(self.root.clone(), self.height, height);
+        let right = raise(other.root.clone(), other.height, height);
+        let mut nodes = merge(&left, &right, height);
+        if nodes.len() == 1 {
+            self.root = nodes.pop().unwrap();
+            self.height = height;
+        } else {
+            self.root = Arc::new(Node::Branch(Branch::new(nodes, height + 1)));
+            self.height = height + 1;
+        }
+        // drop the branches with a single child left on top by raise
+        loop {
+            let child = match &*self.root {
+                Node::Branch(b) if b.children.len() == 1 => b.children[0].clone(),
+                _ => break,
+            };
+            self.root = child;
+            self.height -= 1;
+        }
+    }
+}
+
+fn set<T: Clone>(node: &mut Arc<Node<T>>, height: u32, i: usize, x: T) {
+    match Arc::make_mut(node) {
+        Node::Leaf(items) => items[i] = x,
+        Node::Branch(b) => {
+            let (k, start) = b.locate(i, height);
+            set(&mut b.children[k], height - 1, i - start, x);
+        }
+    }
+}
+
+/// Appends `x` to the subtree at `height`. If the subtree is full,
+/// leaves it alone and returns a new subtree of the same height that
+/// holds just `x`.
+fn push<T: Clone>(node: &mut Arc<Node<T>>, height: u32, x: T) -> Option<Arc<Node<T>>> {
+    if is_full(node) {
+        return Some(raise(Arc::new(Node::Leaf(vec![x])), 0, height));
+    }
+    match Arc::make_mut(node) {
+        Node::Leaf(items) => items.push(x),
+        Node::Branch(b) => {
+            let last = b.children.last_mut().unwrap();
+            if let Some(sibling) = push(last, height - 1, x) {
+                b.children.push(sibling);
+            }
+            b.update(height);
+        }
+    }
+    None
+}
+
+/// Whether nothing can be appended to the subtree without growing it.
+fn is_full<T>(node: &Node<T>) -> bool {
+    match node {
+        Node::Leaf(items) => items.len() == WIDTH,
+        Node::Branch(b) => b.children.len() == WIDTH && is_full(b.children.last().unwrap()),
+    }
+}
+
+/// Puts the subtree under single-child branches up to height `to`.
+fn raise<T>(mut node: Arc<Node<T>>, from: u32, to: u32) -> Arc<Node<T>> {
+    for h in from + 1..=to {
+        node = Arc::new(Node::Branch(Branch::new(vec![node], h)));
+    }
+    node
+}
+
+/// Joins subtrees of the same height, returns one or two subtrees of
+/// that height holding the items of both.
+fn merge<T: Clone>(left: &Arc<Node<T>>, right: &Arc<Node<T>>, height: u32) -> Vec<Arc<Node<T>>> {
+    let (a, b) = match (&**left, &**right) {
+        (Node::Leaf(a), Node::Leaf(b)) => {
+            if a.len() + b.len() <= WIDTH {
+                let items = a.iter().chain(b).cloned().collect();
+                return vec![Arc::new(Node::Leaf(items))];
+            }
+            return vec![left.clone(), right.clone()];
+        }
+        (Node::Branch(a), Node::Branch(b)) => (a, b),
+        _ => unreachable!("subtrees of different heights"),
+    };
+    let (a_last, a_rest) = a.children.split_last().unwrap();
+    let (b_first, b_rest) = b.children.split_first().unwrap();
+    let mut children = a_rest.to_vec();
+    children.extend(merge(a_last, b_first, height - 1));
+    children.extend_from_slice(b_rest);
+    let children = rebalance(children, height - 1);
+    children
+        .chunks(WIDTH)
+        .map(|c| Arc::new(Node::Branch(Branch::new(c.to_vec(), height))))
+        .collect()
+}
+
+/// Repacks sibling subtrees at `height` into as few nodes as possible if
+/// there are too many of them for their slots.
+fn rebalance<T: Clone>(nodes: Vec<Arc<Node<T>>>, height: u32) -> Vec<Arc<Node<T>>> {
+    let slots: usize = nodes.iter().map(|n| n.slots()).sum();
+    if nodes.len() <= slots.div_ceil(WIDTH) + EXTRA {
+        return nodes;
+    }
+    if height == 0 {
+        let items: Vec<T> = nodes
+            .iter()
+            .flat_map(|n| match &**n {
+                Node::Leaf(items) => items.iter().cloned(),
+                Node::Branch(_) => unreachable!(),
+            })
+            .collect();
+        items.chunks(WIDTH).map(|c| Arc::new(Node::Leaf(c.to_vec()))).collect()
+    } else {
+        let children: Vec<Arc<Node<T>>> = nodes
+            .iter()
+            .flat_map(|n| match &**n {
+                Node::Branch(b) => b.children.iter().cloned(),
+                Node::Leaf(_) => unreachable!(),
+            })
+            .collect();
+        children
+            .chunks(WIDTH)
+            .map(|c| Arc::new(Node::Branch(Branch::new(c.to_vec(), height))))
+            .collect()
+    }
+}
+
+pub struct Iter<'a, T> {
+    /// Children left to visit on the path to the current leaf.
+    stack: Vec<slice::Iter<'a, Arc<Node<T>>>>,
+    leaf: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(x) = self.leaf.next() {
+                return Some(x);
+            }
+            let top = self.stack.last_mut()?;
+            match top.next().map(|n| &**n) {
+                Some(Node::Leaf(items)) => self.leaf = items.iter(),
+                Some(Node::Branch(b)) => self.stack.push(b.children.iter()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PersistentVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Clone> FromIterator<T> for PersistentVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        for x in iter {
+            v.push(x);
+        }
+        v
+    }
+}
+
+impl<T> Index<usize> for PersistentVec<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        match self.get(i) {
+            Some(x) => x,
+            None => panic!("index {} out of range for length {}", i, self.len()),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
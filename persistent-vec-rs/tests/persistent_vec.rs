@@ -0,0 +1,184 @@
+//! Random edits against `Vec`, with every earlier version kept and
+//! checked to be unchanged by edits of later ones. Appending vectors of
+//! sizes that don't fill nodes leaves sparse nodes in the middle of the
+//! tree, so `get` there goes through the size tables.
+
+use persistent_vec::PersistentVec;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Sizes around leaf, branch and tree boundaries.
+const SIZES: &[usize] = &[
+    0, 1, 2, 31, 32, 33, 63, 64, 65, 1023, 1024, 1025, 1057, 32767, 32768, 32769, 40000,
+];
+
+fn check(v: &PersistentVec<u32>, model: &[u32]) {
+    assert_eq!(v.len(), model.len());
+    assert_eq!(v.is_empty(), model.is_empty());
+    assert!(v.iter().eq(model.iter()));
+    for (i, x) in model.iter().enumerate() {
+        assert_eq!(v.get(i), Some(x), "index {i}");
+    }
+    assert_eq!(v.get(model.len()), None);
+    assert_eq!(v.get(usize::MAX), None);
+}
+
+/// Vector of `len` items starting at `start`, built by pushes.
+fn pushed(start: u32, len: usize) -> (PersistentVec<u32>, Vec<u32>) {
+    let model: Vec<u32> = (start..start + len as u32).collect();
+    (model.iter().copied().collect(), model)
+}
+
+#[test]
+fn push_and_set() {
+    let mut v = PersistentVec::new();
+    let mut model = Vec::new();
+    let mut snapshots = Vec::new();
+    for i in 0..40_000u32 {
+        v.push(i);
+        model.push(i);
+        if SIZES.contains(&model.len()) {
+            check(&v, &model);
+            snapshots.push((v.clone(), model.clone()));
+        }
+    }
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for _ in 0..1000 {
+        let i = rng.below(model.len() as u64) as usize;
+        let x = rng.next() as u32;
+        v.set(i, x);
+        model[i] = x;
+        assert_eq!(v[i], x);
+    }
+    check(&v, &model);
+    for (s, m) in &snapshots {
+        check(s, m);
+    }
+}
+
+#[test]
+fn append_all_size_pairs() {
+    for &a in SIZES {
+        for &b in SIZES {
+            let (mut left, mut model) = pushed(0, a);
+            let (right, right_model) = pushed(1_000_000, b);
+            let before = left.clone();
+            left.append(&right);
+            model.extend(&right_model);
+            check(&left, &model);
+            // neither operand changed
+            check(&before, &model[..a]);
+            check(&right, &right_model);
+            // the result takes pushes and sets like any vector
+            left.push(7);
+            model.push(7);
+            if let Some(i) = model.len().checked_sub(2) {
+                left.set(i / 2, 8);
+                model[i / 2] = 8;
+            }
+            check(&left, &model);
+            check(&right, &right_model);
+        }
+    }
+}
+
+#[test]
+fn self_append() {
+    for &len in SIZES {
+        let (mut v, mut model) = pushed(0, len.min(5000));
+        for _ in 0..3 {
+            let copy = v.clone();
+            v.append(&copy);
+            model.extend_from_within(..);
+            check(&v, &model);
+            check(&copy, &model[..model.len() / 2]);
+        }
+    }
+}
+
+#[test]
+fn random_edits_keep_snapshots() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    let mut v = PersistentVec::new();
+    let mut model: Vec<u32> = Vec::new();
+    let mut snapshots: Vec<(PersistentVec<u32>, Vec<u32>)> = Vec::new();
+    let mut next = 0;
+    for _ in 0..400 {
+        match rng.below(10) {
+            0..=3 => {
+                for _ in 0..rng.below(100) {
+                    v.push(next);
+                    model.push(next);
+                    next += 1;
+                }
+            }
+            4..=5 if !model.is_empty() => {
+                for _ in 0..rng.below(50) {
+                    let i = rng.below(model.len() as u64) as usize;
+                    v.set(i, next);
+                    model[i] = next;
+                    next += 1;
+                }
+            }
+            6..=8 => {
+                // odd sizes leave sparse nodes along the seams
+                let len = rng.below(200) as usize;
+                let (other, other_model) = pushed(next, len);
+                next += len as u32;
+                v.append(&other);
+                model.extend(other_model);
+            }
+            _ if model.len() < 20_000 => {
+                let copy = v.clone();
+                v.append(&copy);
+                model.extend_from_within(..);
+            }
+            _ => {}
+        }
+        assert_eq!(v.len(), model.len());
+        if rng.below(10) == 0 {
+            check(&v, &model);
+            snapshots.push((v.clone(), model.clone()));
+        }
+    }
+    check(&v, &model);
+    for (s, m) in &snapshots {
+        check(s, m);
+    }
+}
+
+#[test]
+fn many_small_appends() {
+    // concatenations only, no vector ever filled by pushes
+    let mut v = PersistentVec::new();
+    let mut model = Vec::new();
+    let mut next = 0;
+    for i in 0..3000 {
+        let len = [1, 3, 17, 33][i % 4];
+        let (other, other_model) = pushed(next, len);
+        next += len as u32;
+        v.append(&other);
+        model.extend(other_model);
+    }
+    check(&v, &model);
+    let mut rng = Rng(7);
+    for _ in 0..1000 {
+        let i = rng.below(model.len() as u64) as usize;
+        v.set(i, 0);
+        model[i] = 0;
+    }
+    check(&v, &model);
+}
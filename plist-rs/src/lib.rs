@@ -0,0 +1,195 @@
+//! Persistent list: an immutable singly linked list whose clones share
+//! structure.
+//!
+//! A list is a reference counted pointer to its first node, each node
+//! pointing to the rest of the list. Lists are never changed: pushing to
+//! the front makes a new node pointing to the old list, which stays as it
+//! was, and the tail of a list is just the list its first node points
+//! to. So many lists can share a common tail, and cloning, pushing and
+//! taking the tail are all O(1).
+//!
+//! Dropping a long list drops its nodes in a loop rather than
+//! recursively, stopping at the first node still shared.
+//!
+//! See https://en.wikipedia.org/wiki/Cons
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+type Link<T> = Option<Arc<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+    /// Length of the list starting at this node.
+    len: usize,
+}
+
+pub struct PList<T> {
+    head: Link<T>,
+}
+
+impl<T> Clone for PList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T> Default for PList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> PList<T> {
+    pub const fn new() -> Self {
+        Self { head: None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.head.as_ref().map_or(0, |n| n.len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// New list with `value` in front of this one.
+    pub fn push_front(&self, value: T) -> Self {
+        Self {
+            head: Some(Arc::new(Node {
+                value,
+                next: self.head.clone(),
+                len: self.len() + 1,
+            })),
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.as_ref().map(|n| &n.value)
+    }
+
+    /// List without its first element, `None` if empty.
+    pub fn tail(&self) -> Option<Self> {
+        self.head.as_ref().map(|n| Self {
+            head: n.next.clone(),
+        })
+    }
+
+    /// First element and the rest of the list.
+    pub fn split_first(&self) -> Option<(&T, Self)> {
+        self.head.as_ref().map(|n| {
+            let rest = Self {
+                head: n.next.clone(),
+            };
+            (&n.value, rest)
+        })
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    /// New list with elements in reverse order, sharing nothing.
+    pub fn reverse(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.iter()
+            .fold(Self::new(), |list, x| list.push_front(x.clone()))
+    }
+
+    /// Whether both lists are the same nodes, not just equal elements.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T> Drop for PList<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(node) = link {
+            // unlike `try_unwrap`, exactly one of the threads dropping the
+            // last references gets the node, so the rest is never dropped
+            // recursively
+            match Arc::into_inner(node) {
+                Some(mut node) => link = node.next.take(),
+                // rest of the list is still shared
+                None => break,
+            }
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next?;
+        self.next = node.next.as_deref();
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.next.map_or(0, |n| n.len);
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a PList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> FromIterator<T> for PList<T> {
+    /// List with elements in iteration order.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        items
+            .into_iter()
+            .rev()
+            .fold(Self::new(), |list, x| list.push_front(x))
+    }
+}
+
+impl<T: PartialEq> PartialEq for PList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for PList<T> {}
+
+impl<T: Hash> Hash for PList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for x in self {
+            x.hash(state);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
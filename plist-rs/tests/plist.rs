@@ -0,0 +1,120 @@
+//! Lists share their tails and are never changed by making new ones;
+//! long lists drop without overflowing the stack.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use plist::PList;
+
+#[test]
+fn push_and_tail_share_nodes() {
+    let empty = PList::new();
+    let a = empty.push_front(1);
+    let b = a.push_front(2);
+    let c = a.push_front(3);
+    // pushing leaves the old list as it was
+    assert!(empty.is_empty());
+    assert_eq!(a.iter().copied().collect::<Vec<_>>(), [1]);
+    assert_eq!(b.iter().copied().collect::<Vec<_>>(), [2, 1]);
+    assert_eq!(c.iter().copied().collect::<Vec<_>>(), [3, 1]);
+    // both tails are the very nodes of `a`
+    assert!(b.tail().unwrap().ptr_eq(&a));
+    assert!(c.tail().unwrap().ptr_eq(&a));
+    assert!(a.tail().unwrap().ptr_eq(&empty));
+    assert!(empty.tail().is_none());
+    assert!(b.clone().ptr_eq(&b));
+    // equal elements, different nodes
+    let d = PList::new().push_front(1);
+    assert_eq!(d, a);
+    assert!(!d.ptr_eq(&a));
+    let (first, rest) = b.split_first().unwrap();
+    assert_eq!(*first, 2);
+    assert!(rest.ptr_eq(&a));
+    assert_eq!(b.front(), Some(&2));
+}
+
+#[test]
+fn from_iter_keeps_order() {
+    let list: PList<i32> = (0..10).collect();
+    assert!(list.iter().copied().eq(0..10));
+    assert_eq!(list.front(), Some(&0));
+    assert_eq!(
+        format!("{:?}", list),
+        format!("{:?}", (0..10).collect::<Vec<_>>())
+    );
+    let empty: PList<i32> = std::iter::empty().collect();
+    assert!(empty.is_empty());
+    assert_eq!(empty.front(), None);
+}
+
+#[test]
+fn reverse_shares_nothing() {
+    let list: PList<i32> = (0..100).collect();
+    let rev = list.reverse();
+    assert!(rev.iter().copied().eq((0..100).rev()));
+    assert!(list.iter().copied().eq(0..100));
+    assert_eq!(rev.reverse(), list);
+    assert!(!rev.reverse().ptr_eq(&list));
+    assert!(PList::<i32>::new().reverse().is_empty());
+}
+
+#[test]
+fn len_of_every_suffix() {
+    let mut list: PList<usize> = (0..50).collect();
+    for n in (0..=50).rev() {
+        assert_eq!(list.len(), n);
+        assert_eq!(list.iter().len(), n);
+        assert_eq!(list.is_empty(), n == 0);
+        let mut it = list.iter();
+        for m in (0..n).rev() {
+            it.next();
+            assert_eq!(it.len(), m);
+        }
+        if let Some(tail) = list.tail() {
+            list = tail;
+        }
+    }
+}
+
+#[test]
+fn long_list_drops() {
+    let list: PList<u32> = (0..1_000_000).collect();
+    let shared = list.tail().unwrap();
+    drop(list);
+    assert_eq!(shared.len(), 999_999);
+    drop(shared);
+
+    // a shared tail dropped on several threads at once
+    let list: PList<u32> = (0..1_000_000).collect();
+    thread::scope(|s| {
+        for i in 0..4 {
+            let list = list.push_front(i);
+            s.spawn(move || drop(list));
+        }
+    });
+    drop(list);
+}
+
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn shared_values_dropped_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let a: PList<Counted> = (0..10).map(|_| Counted(drops.clone())).collect();
+    let b = a.push_front(Counted(drops.clone()));
+    let c = a.tail().unwrap();
+    drop(a);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(b);
+    // `b`'s own node and the first node of `a` only
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+    drop(c);
+    assert_eq!(drops.load(Ordering::Relaxed), 11);
+}
@@ -0,0 +1,133 @@
+//! Object pool: reuse expensive objects instead of creating them anew.
+//!
+//! `get` checks out an idle object, or makes one with the pool's factory
+//! if there are none. The object comes in a guard that puts it back when
+//! dropped, so it returns to the pool however its user finishes with it.
+//! Objects put back while the pool already holds `max_idle` idle ones are
+//! dropped, keeping a burst of demand from pinning memory for good.
+//!
+//! The pool is shared between threads by reference, e.g. in an `Arc`.
+//! Objects are put back as they are, resetting them (like clearing a
+//! buffer) is up to the user.
+//!
+//! See https://en.wikipedia.org/wiki/Object_pool_pattern
+
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+type Factory<T> = Box<dyn Fn() -> T + Send + Sync>;
+
+pub struct Pool<T> {
+    idle: Mutex<Vec<T>>,
+    max_idle: usize,
+    factory: Factory<T>,
+}
+
+impl<T> Pool<T> {
+    /// Creates a pool making objects with `factory`, keeping all objects
+    /// put back.
+    pub fn new(factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self::with_max_idle(usize::MAX, factory)
+    }
+
+    /// Creates a pool keeping at most `max_idle` idle objects.
+    pub fn with_max_idle(max_idle: usize, factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+            factory: Box::new(factory),
+        }
+    }
+
+    /// Lock on the idle objects. A thread panicking while holding it
+    /// can't leave the list half-changed, so poisoning is ignored.
+    fn lock(&self) -> MutexGuard<'_, Vec<T>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Checks out an idle object, or a new one if none is idle.
+    pub fn get(&self) -> PoolGuard<'_, T> {
+        let obj = self.lock().pop();
+        // factory runs outside the lock, it may be slow
+        let obj = obj.unwrap_or_else(|| (self.factory)());
+        PoolGuard {
+            pool: self,
+            obj: ManuallyDrop::new(obj),
+        }
+    }
+
+    /// Puts an object into the pool, as if it was checked out.
+    pub fn put(&self, obj: T) {
+        let mut idle = self.lock();
+        if idle.len() < self.max_idle {
+            idle.push(obj);
+        } else {
+            drop(idle);
+            drop(obj);
+        }
+    }
+
+    /// Number of idle objects.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn max_idle(&self) -> usize {
+        self.max_idle
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("idle", &self.idle())
+            .field("max_idle", &self.max_idle)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Object checked out of a pool, put back when dropped.
+pub struct PoolGuard<'a, T> {
+    pool: &'a Pool<T>,
+    obj: ManuallyDrop<T>,
+}
+
+impl<T> PoolGuard<'_, T> {
+    /// Takes the object out of the pool for good.
+    pub fn detach(guard: Self) -> T {
+        let mut guard = ManuallyDrop::new(guard);
+        // SAFETY: the guard is not dropped, so the object is taken once
+        unsafe { ManuallyDrop::take(&mut guard.obj) }
+    }
+}
+
+impl<T> Deref for PoolGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.obj
+    }
+}
+
+impl<T> DerefMut for PoolGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.obj
+    }
+}
+
+impl<T> Drop for PoolGuard<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the object is taken only here or in `detach`, which
+        // doesn't drop the guard
+        let obj = unsafe { ManuallyDrop::take(&mut self.obj) };
+        self.pool.put(obj);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PoolGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,120 @@
+//! Objects are reused, put back by guards and detached, and threads
+//! checking out and returning objects at once never leave more than
+//! `max_idle` of them idle, with every object made either idle, dropped
+//! or detached.
+
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+use pool::{Pool, PoolGuard};
+
+/// Counts objects made and dropped.
+#[derive(Default)]
+struct Counts {
+    made: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+struct Obj {
+    id: usize,
+    counts: Arc<Counts>,
+}
+
+impl Drop for Obj {
+    fn drop(&mut self) {
+        self.counts.dropped.fetch_add(1, Relaxed);
+    }
+}
+
+fn pool(max_idle: usize) -> (Pool<Obj>, Arc<Counts>) {
+    let counts = Arc::new(Counts::default());
+    let c = counts.clone();
+    let pool = Pool::with_max_idle(max_idle, move || Obj {
+        id: c.made.fetch_add(1, Relaxed),
+        counts: c.clone(),
+    });
+    (pool, counts)
+}
+
+#[test]
+fn reuse_and_detach() {
+    let (pool, counts) = pool(2);
+    let a = pool.get();
+    let id = a.id;
+    drop(a);
+    assert_eq!(pool.idle(), 1);
+    assert_eq!(pool.get().id, id);
+    assert_eq!(counts.made.load(Relaxed), 1);
+
+    let (a, b, c) = (pool.get(), pool.get(), pool.get());
+    assert_eq!(counts.made.load(Relaxed), 3);
+    drop((a, b, c));
+    // the third one put back is over the cap
+    assert_eq!(pool.idle(), 2);
+    assert_eq!(counts.dropped.load(Relaxed), 1);
+
+    let obj = PoolGuard::detach(pool.get());
+    assert_eq!(pool.idle(), 1);
+    pool.put(obj);
+    assert_eq!(pool.idle(), 2);
+    drop(pool);
+    assert_eq!(counts.dropped.load(Relaxed), 3);
+}
+
+#[test]
+fn zero_max_idle_keeps_nothing() {
+    let (pool, counts) = pool(0);
+    for _ in 0..10 {
+        drop(pool.get());
+    }
+    assert_eq!(pool.idle(), 0);
+    assert_eq!(counts.made.load(Relaxed), 10);
+    assert_eq!(counts.dropped.load(Relaxed), 10);
+}
+
+#[test]
+fn idle_cap_across_threads() {
+    let threads = 8;
+    let rounds = if cfg!(miri) { 5 } else { 1000 };
+    for max_idle in [0, 1, 3, 8, 100] {
+        let (pool, counts) = pool(max_idle);
+        let detached = AtomicUsize::new(0);
+        let barrier = Barrier::new(threads);
+        thread::scope(|s| {
+            for t in 0..threads {
+                let (pool, detached, barrier) = (&pool, &detached, &barrier);
+                s.spawn(move || {
+                    for round in 0..rounds {
+                        // several objects out per thread at once, so
+                        // more come back than the cap allows
+                        let held: Vec<_> = (0..1 + (t + round) % 3).map(|_| pool.get()).collect();
+                        if round % 10 == 0 {
+                            barrier.wait();
+                        }
+                        for (i, g) in held.into_iter().enumerate() {
+                            if (t + round + i) % 17 == 0 {
+                                drop(PoolGuard::detach(g));
+                                detached.fetch_add(1, Relaxed);
+                            } else {
+                                drop(g);
+                            }
+                            assert!(pool.idle() <= max_idle);
+                        }
+                    }
+                });
+            }
+        });
+        let idle = pool.idle();
+        assert!(idle <= max_idle, "{} idle over {}", idle, max_idle);
+        let made = counts.made.load(Relaxed);
+        // detached objects were dropped by their threads
+        assert_eq!(made - counts.dropped.load(Relaxed), idle);
+        assert!(detached.load(Relaxed) > 0);
+        // all threads held objects at the barrier, at least that many
+        // were made
+        assert!(made >= threads);
+        drop(pool);
+        assert_eq!(counts.dropped.load(Relaxed), made);
+    }
+}
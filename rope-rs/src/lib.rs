@@ -0,0 +1,411 @@
+//! Rope: text as a balanced tree of string chunks.
+//!
+//! In-order traversal of the tree gives the text. Every node keeps the
+//! number of chars and newlines in its subtree, so a char or line can be
+//! found by descending from the root. The tree is a treap: nodes have
+//! random priorities and parents have higher priority than children,
+//! which makes the tree balanced with high probability, i.e. its depth
+//! is O(log n) for n chunks. Edits split and merge treaps in O(log n).
+//!
+//! Positions are counted in chars (unicode scalar values), lines are
+//! separated by '\n'.
+//!
+//! See https://en.wikipedia.org/wiki/Rope_(data_structure)
+//! and https://en.wikipedia.org/wiki/Treap
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+/// Text is stored in chunks of at most this many bytes, except that
+/// a chunk can't be cut in the middle of a char.
+const CHUNK_SIZE: usize = 512;
+
+type Link = Option<Box<Node>>;
+
+struct Node {
+    text: String,
+    priority: u64,
+    /// Chars and newlines in the whole subtree.
+    chars: usize,
+    lines: usize,
+    left: Link,
+    right: Link,
+}
+
+fn chars(link: &Link) -> usize {
+    link.as_ref().map_or(0, |n| n.chars)
+}
+
+fn lines(link: &Link) -> usize {
+    link.as_ref().map_or(0, |n| n.lines)
+}
+
+fn count_lines(s: &str) -> usize {
+    s.bytes().filter(|&b| b == b'\n').count()
+}
+
+/// Byte offset of char `i` in `s`, or `s.len()` if `i` is past the end.
+fn byte_offset(s: &str, i: usize) -> usize {
+    s.char_indices().nth(i).map_or(s.len(), |(b, _)| b)
+}
+
+impl Node {
+    fn new(text: String, priority: u64) -> Box<Self> {
+        let mut node = Box::new(Self {
+            text,
+            priority,
+            chars: 0,
+            lines: 0,
+            left: None,
+            right: None,
+        });
+        node.update();
+        node
+    }
+
+    fn update(&mut self) {
+        self.chars = chars(&self.left) + self.text.chars().count() + chars(&self.right);
+        self.lines = lines(&self.left) + count_lines(&self.text) + lines(&self.right);
+    }
+}
+
+/// Merges treaps with all of `a` going before all of `b`.
+fn merge(a: Link, b: Link) -> Link {
+    match (a, b) {
+        (None, b) => b,
+        (a, None) => a,
+        (Some(mut a), Some(mut b)) => {
+            if a.priority > b.priority {
+                a.right = merge(a.right.take(), Some(b));
+                a.update();
+                Some(a)
+            } else {
+                b.left = merge(Some(a), b.left.take());
+                b.update();
+                Some(b)
+            }
+        }
+    }
+}
+
+pub struct Rope {
+    root: Link,
+    rng: u64,
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            rng: 0x2545f4914f6cdd1d,
+        }
+    }
+
+    /// xorshift64 for node priorities
+    fn random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    pub fn len_chars(&self) -> usize {
+        chars(&self.root)
+    }
+
+    /// Number of lines, which is one more than the number of newlines.
+    pub fn len_lines(&self) -> usize {
+        lines(&self.root) + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Splits into the first `at` chars and the rest.
+    fn split(&mut self, link: Link, at: usize) -> (Link, Link) {
+        let Some(mut node) = link else {
+            return (None, None);
+        };
+        let left_chars = chars(&node.left);
+        let text_chars = node.chars - left_chars - chars(&node.right);
+        if at <= left_chars {
+            let (a, b) = self.split(node.left.take(), at);
+            node.left = b;
+            node.update();
+            (a, Some(node))
+        } else if at >= left_chars + text_chars {
+            let (a, b) = self.split(node.right.take(), at - left_chars - text_chars);
+            node.right = a;
+            node.update();
+            (Some(node), b)
+        } else {
+            // split falls inside this chunk, second half gets a new node
+            let tail = node.text.split_off(byte_offset(&node.text, at - left_chars));
+            let tail = Node::new(tail, self.random());
+            let right = merge(Some(tail), node.right.take());
+            node.update();
+            (Some(node), right)
+        }
+    }
+
+    /// Builds treap of chunks of `s`.
+    fn build(&mut self, mut s: &str) -> Link {
+        let mut link = None;
+        while !s.is_empty() {
+            let mut end = s.len().min(CHUNK_SIZE);
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            let node = Node::new(s[..end].to_string(), self.random());
+            link = merge(link, Some(node));
+            s = &s[end..];
+        }
+        link
+    }
+
+    /// Inserts `s` at char index `at`.
+    pub fn insert(&mut self, at: usize, s: &str) {
+        assert!(at <= self.len_chars(), "index {} out of range for length {}", at, self.len_chars());
+        if s.is_empty() {
+            return;
+        }
+        // small insertions go straight into a chunk with room for them
+        if let Some(root) = &mut self.root {
+            if insert_into_chunk(root, at, s, s.chars().count(), count_lines(s)) {
+                return;
+            }
+        }
+        let root = self.root.take();
+        let (a, b) = self.split(root, at);
+        let mid = self.build(s);
+        self.root = merge(merge(a, mid), b);
+    }
+
+    fn range(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => self.len_chars(),
+        };
+        assert!(start <= end, "range start {} is greater than end {}", start, end);
+        assert!(end <= self.len_chars(), "range end {} out of range for length {}", end, self.len_chars());
+        (start, end)
+    }
+
+    /// Removes chars in `range`.
+    pub fn remove(&mut self, range: impl RangeBounds<usize>) {
+        let (start, end) = self.range(range);
+        if start == end {
+            return;
+        }
+        let root = self.root.take();
+        let (rest, tail) = self.split(root, end);
+        let (head, _) = self.split(rest, start);
+        self.root = merge(head, tail);
+    }
+
+    pub fn char_at(&self, i: usize) -> char {
+        assert!(i < self.len_chars(), "index {} out of range for length {}", i, self.len_chars());
+        let mut node = self.root.as_ref().unwrap();
+        let mut i = i;
+        loop {
+            let left_chars = chars(&node.left);
+            if i < left_chars {
+                node = node.left.as_ref().unwrap();
+                continue;
+            }
+            i -= left_chars;
+            match node.text.chars().nth(i) {
+                Some(c) => return c,
+                None => {
+                    i -= node.text.chars().count();
+                    node = node.right.as_ref().unwrap();
+                }
+            }
+        }
+    }
+
+    /// Text of chars in `range`.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> String {
+        let (start, end) = self.range(range);
+        let mut s = String::new();
+        collect(&self.root, start, end, &mut s);
+        s
+    }
+
+    /// Line that char `i` is on. `i` may be the length of the rope.
+    pub fn char_to_line(&self, i: usize) -> usize {
+        assert!(i <= self.len_chars(), "index {} out of range for length {}", i, self.len_chars());
+        let mut link = &self.root;
+        let mut i = i;
+        let mut line = 0;
+        while let Some(node) = link {
+            let left_chars = chars(&node.left);
+            if i <= left_chars {
+                link = &node.left;
+                continue;
+            }
+            i -= left_chars;
+            line += lines(&node.left);
+            let text_chars = node.chars - left_chars - chars(&node.right);
+            if i <= text_chars {
+                return line + count_lines(&node.text[..byte_offset(&node.text, i)]);
+            }
+            i -= text_chars;
+            line += count_lines(&node.text);
+            link = &node.right;
+        }
+        line
+    }
+
+    /// Index of the first char of `line`. `line` may be the number of
+    /// lines, then it's the length of the rope.
+    pub fn line_to_char(&self, line: usize) -> usize {
+        assert!(line <= self.len_lines(), "line {} out of range for {} lines", line, self.len_lines());
+        if line == 0 {
+            return 0;
+        }
+        if line == self.len_lines() {
+            return self.len_chars();
+        }
+        // find the newline ending the previous line
+        let mut node = self.root.as_ref().unwrap();
+        let mut k = line - 1;
+        let mut pos = 0;
+        loop {
+            let left_lines = lines(&node.left);
+            if k < left_lines {
+                node = node.left.as_ref().unwrap();
+                continue;
+            }
+            k -= left_lines;
+            pos += chars(&node.left);
+            let text_lines = node.lines - left_lines - lines(&node.right);
+            if k < text_lines {
+                let (i, _) = node.text.chars().enumerate().filter(|&(_, c)| c == '\n').nth(k).unwrap();
+                return pos + i + 1;
+            }
+            k -= text_lines;
+            pos += node.text.chars().count();
+            node = node.right.as_ref().unwrap();
+        }
+    }
+
+    /// Chunks of text in order.
+    pub fn chunks(&self) -> Chunks<'_> {
+        let mut chunks = Chunks { stack: Vec::new() };
+        chunks.push_left(&self.root);
+        chunks
+    }
+}
+
+/// Inserts `s` into the chunk holding position `at` if it fits there.
+fn insert_into_chunk(node: &mut Node, at: usize, s: &str, s_chars: usize, s_lines: usize) -> bool {
+    let left_chars = chars(&node.left);
+    let text_chars = node.chars - left_chars - chars(&node.right);
+    let done = if at < left_chars {
+        node.left
+            .as_mut()
+            .is_some_and(|n| insert_into_chunk(n, at, s, s_chars, s_lines))
+    } else if at <= left_chars + text_chars {
+        if node.text.len() + s.len() > CHUNK_SIZE {
+            return false;
+        }
+        let b = byte_offset(&node.text, at - left_chars);
+        node.text.insert_str(b, s);
+        true
+    } else {
+        let at = at - left_chars - text_chars;
+        node.right
+            .as_mut()
+            .is_some_and(|n| insert_into_chunk(n, at, s, s_chars, s_lines))
+    };
+    if done {
+        node.chars += s_chars;
+        node.lines += s_lines;
+    }
+    done
+}
+
+/// Appends chars `start..end` of subtree to `s`.
+fn collect(link: &Link, start: usize, end: usize, s: &mut String) {
+    let Some(node) = link else {
+        return;
+    };
+    if start >= end {
+        return;
+    }
+    let left_chars = chars(&node.left);
+    let text_chars = node.chars - left_chars - chars(&node.right);
+    if start < left_chars {
+        collect(&node.left, start, end.min(left_chars), s);
+    }
+    let (lo, hi) = (start.max(left_chars), end.min(left_chars + text_chars));
+    if lo < hi {
+        let from = byte_offset(&node.text, lo - left_chars);
+        let to = byte_offset(&node.text, hi - left_chars);
+        s.push_str(&node.text[from..to]);
+    }
+    if end > left_chars + text_chars {
+        let skip = left_chars + text_chars;
+        collect(&node.right, start.saturating_sub(skip), end - skip, s);
+    }
+}
+
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Chunks<'a> {
+    fn push_left(&mut self, mut link: &'a Link) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        Some(&node.text)
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(s: &str) -> Self {
+        let mut rope = Rope::new();
+        rope.root = rope.build(s);
+        rope
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string(), f)
+    }
+}
@@ -0,0 +1,143 @@
+//! Random edits of multibyte text against `String`, with char and line
+//! lookups checked at every position. Texts are long enough for edits
+//! to cross chunk boundaries.
+
+use rope::Rope;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    /// Random text of 1 to 4 byte chars and newlines.
+    fn text(&mut self, max_chars: u64) -> String {
+        let len = self.below(max_chars + 1);
+        (0..len)
+            .map(|_| ['a', 'b', '\n', 'é', 'ж', '日', '🎉'][self.below(7) as usize])
+            .collect()
+    }
+}
+
+fn check(rope: &Rope, model: &str) {
+    let chars: Vec<char> = model.chars().collect();
+    assert_eq!(rope.to_string(), model);
+    assert_eq!(rope.len_chars(), chars.len());
+    assert_eq!(rope.is_empty(), model.is_empty());
+    assert_eq!(rope.len_lines(), model.split('\n').count());
+    for chunk in rope.chunks() {
+        assert!(
+            !chunk.is_empty() && chunk.len() <= 512,
+            "chunk of {} bytes",
+            chunk.len()
+        );
+    }
+
+    let mut line = 0;
+    let mut line_starts = vec![0];
+    for (i, &c) in chars.iter().enumerate() {
+        assert_eq!(rope.char_at(i), c);
+        assert_eq!(rope.char_to_line(i), line, "line of char {}", i);
+        if c == '\n' {
+            line += 1;
+            line_starts.push(i + 1);
+        }
+    }
+    assert_eq!(rope.char_to_line(chars.len()), line);
+    for (l, &start) in line_starts.iter().enumerate() {
+        assert_eq!(rope.line_to_char(l), start, "start of line {}", l);
+    }
+    assert_eq!(rope.line_to_char(line_starts.len()), chars.len());
+}
+
+/// Byte offset of char `i` in `s`.
+fn byte(s: &str, i: usize) -> usize {
+    s.char_indices().nth(i).map_or(s.len(), |(b, _)| b)
+}
+
+#[test]
+fn random_edits() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let mut rope = Rope::new();
+    let mut model = String::new();
+    for step in 0..600 {
+        let len = model.chars().count() as u64;
+        if rng.below(3) != 0 || len == 0 {
+            // mostly short insertions, sometimes several chunks at once
+            let max = if rng.below(10) == 0 { 2000 } else { 20 };
+            let s = rng.text(max);
+            let at = rng.below(len + 1) as usize;
+            rope.insert(at, &s);
+            model.insert_str(byte(&model, at), &s);
+        } else {
+            let a = rng.below(len + 1) as usize;
+            let b = rng.below(len + 1) as usize;
+            let (start, end) = (a.min(b), a.max(b));
+            rope.remove(start..end);
+            model.replace_range(byte(&model, start)..byte(&model, end), "");
+        }
+        if step % 50 == 0 {
+            check(&rope, &model);
+        }
+    }
+    check(&rope, &model);
+}
+
+#[test]
+fn remove_across_chunks() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..15 {
+        let text = rng.text(3000);
+        let len = text.chars().count();
+        let rope = Rope::from(text.as_str());
+        check(&rope, &text);
+        for _ in 0..5 {
+            // ranges hundreds of chars long span several chunks
+            let start = rng.below(len as u64 + 1) as usize;
+            let end = (start + rng.below(1500) as usize).min(len);
+            let mut r = Rope::from(text.as_str());
+            r.remove(start..end);
+            let mut expected = text.clone();
+            expected.replace_range(byte(&text, start)..byte(&text, end), "");
+            check(&r, &expected);
+            assert_eq!(
+                rope.slice(start..end),
+                text[byte(&text, start)..byte(&text, end)]
+            );
+        }
+    }
+}
+
+#[test]
+fn remove_everything() {
+    let text = "日本語\n".repeat(500);
+    let mut rope = Rope::from(text.as_str());
+    rope.remove(..);
+    assert!(rope.is_empty());
+    check(&rope, "");
+    rope.insert(0, "🎉\n");
+    check(&rope, "🎉\n");
+}
+
+#[test]
+fn lines_of_small_texts() {
+    for text in ["", "\n", "\n\n", "a\nb", "é\n", "\néé\n日"] {
+        check(&Rope::from(text), text);
+    }
+    let rope = Rope::from("ab\nжж\n\n🎉");
+    assert_eq!(rope.char_to_line(2), 0);
+    assert_eq!(rope.char_to_line(3), 1);
+    assert_eq!(rope.char_to_line(6), 2);
+    assert_eq!(rope.char_to_line(7), 3);
+    assert_eq!(rope.line_to_char(1), 3);
+    assert_eq!(rope.line_to_char(3), 7);
+    assert_eq!(rope.line_to_char(4), 8);
+}
@@ -0,0 +1,360 @@
+//! Segment tree with lazy propagation.
+//!
+//! See https://en.wikipedia.org/wiki/Segment_tree
+
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+/// Associative operation with an identity element.
+pub trait Monoid {
+    type Item: Clone;
+
+    fn identity() -> Self::Item;
+    fn op(a: &Self::Item, b: &Self::Item) -> Self::Item;
+}
+
+/// Range update applied to monoid values.
+///
+/// `apply` gets the length of the segment `x` was combined from, and must
+/// distribute over `op`, i.e. applying `f` to a combined segment must give
+/// the same result as combining its parts with `f` applied to each of them.
+pub trait Action<M: Monoid> {
+    type Update: Clone;
+
+    fn apply(f: &Self::Update, x: &M::Item, len: usize) -> M::Item;
+    /// Update equivalent to applying `older` first, then `newer`.
+    fn compose(newer: &Self::Update, older: &Self::Update) -> Self::Update;
+}
+
+pub struct SegmentTree<M: Monoid, A: Action<M>> {
+    len: usize,
+    tree: Vec<M::Item>,
+    lazy: Vec<Option<A::Update>>,
+}
+
+impl<M: Monoid, A: Action<M>> SegmentTree<M, A> {
+    /// Creates a tree of `len` identity elements.
+    pub fn new(len: usize) -> Self {
+        Self::from_vec(vec![M::identity(); len])
+    }
+
+    pub fn from_vec(v: Vec<M::Item>) -> Self {
+        let len = v.len();
+        let size = 2 * len.next_power_of_two();
+        let mut st = Self {
+            len,
+            tree: vec![M::identity(); size],
+            lazy: vec![None; size],
+        };
+        if len > 0 {
+            st.build(1, 0, len, &v);
+        }
+        st
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Combines all elements in `range`. Identity for an empty range.
+    pub fn query(&self, range: impl RangeBounds<usize>) -> M::Item {
+        let (l, r) = self.bounds(range);
+        if l == r {
+            return M::identity();
+        }
+        self.query_rec(1, 0, self.len, l, r)
+    }
+
+    pub fn get(&self, i: usize) -> M::Item {
+        assert!(i < self.len, "index {} out of range for length {}", i, self.len);
+        self.query(i..=i)
+    }
+
+    /// Applies update `f` to all elements in `range`.
+    pub fn update(&mut self, range: impl RangeBounds<usize>, f: A::Update) {
+        let (l, r) = self.bounds(range);
+        if l == r {
+            return;
+        }
+        self.update_rec(1, 0, self.len, l, r, &f);
+    }
+
+    /// Replaces element `i` with `x`.
+    pub fn set(&mut self, i: usize, x: M::Item) {
+        assert!(i < self.len, "index {} out of range for length {}", i, self.len);
+        self.set_rec(1, 0, self.len, i, x);
+    }
+
+    fn bounds(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => self.len,
+        };
+        assert!(l <= r, "range start {} is greater than end {}", l, r);
+        assert!(r <= self.len, "range end {} out of range for length {}", r, self.len);
+        (l, r)
+    }
+
+    fn build(&mut self, node: usize, l: usize, r: usize, v: &[M::Item]) {
+        if r - l == 1 {
+            self.tree[node] = v[l].clone();
+            return;
+        }
+        let mid = l + (r - l) / 2;
+        self.build(2 * node, l, mid, v);
+        self.build(2 * node + 1, mid, r, v);
+        self.pull(node);
+    }
+
+    fn pull(&mut self, node: usize) {
+        self.tree[node] = M::op(&self.tree[2 * node], &self.tree[2 * node + 1]);
+    }
+
+    fn apply_node(&mut self, node: usize, len: usize, f: &A::Update) {
+        self.tree[node] = A::apply(f, &self.tree[node], len);
+        if len > 1 {
+            self.lazy[node] = Some(match &self.lazy[node] {
+                Some(g) => A::compose(f, g),
+                None => f.clone(),
+            });
+        }
+    }
+
+    /// Pushes pending update of `node` down to its children.
+    fn push(&mut self, node: usize, l: usize, mid: usize, r: usize) {
+        if let Some(f) = self.lazy[node].take() {
+            self.apply_node(2 * node, mid - l, &f);
+            self.apply_node(2 * node + 1, r - mid, &f);
+        }
+    }
+
+    fn query_rec(&self, node: usize, l: usize, r: usize, ql: usize, qr: usize) -> M::Item {
+        if ql <= l && r <= qr {
+            return self.tree[node].clone();
+        }
+        let mid = l + (r - l) / 2;
+        let x = if qr <= mid {
+            self.query_rec(2 * node, l, mid, ql, qr)
+        } else if ql >= mid {
+            self.query_rec(2 * node + 1, mid, r, ql, qr)
+        } else {
+            M::op(
+                &self.query_rec(2 * node, l, mid, ql, qr),
+                &self.query_rec(2 * node + 1, mid, r, ql, qr),
+            )
+        };
+        // pending update of this node hasn't reached the children yet,
+        // so apply it to the part of the range we got from them
+        match &self.lazy[node] {
+            Some(f) => A::apply(f, &x, qr.min(r) - ql.max(l)),
+            None => x,
+        }
+    }
+
+    fn update_rec(&mut self, node: usize, l: usize, r: usize, ql: usize, qr: usize, f: &A::Update) {
+        if ql <= l && r <= qr {
+            self.apply_node(node, r - l, f);
+            return;
+        }
+        let mid = l + (r - l) / 2;
+        self.push(node, l, mid, r);
+        if ql < mid {
+            self.update_rec(2 * node, l, mid, ql, qr, f);
+        }
+        if qr > mid {
+            self.update_rec(2 * node + 1, mid, r, ql, qr, f);
+        }
+        self.pull(node);
+    }
+
+    fn set_rec(&mut self, node: usize, l: usize, r: usize, i: usize, x: M::Item) {
+        if r - l == 1 {
+            self.tree[node] = x;
+            return;
+        }
+        let mid = l + (r - l) / 2;
+        self.push(node, l, mid, r);
+        if i < mid {
+            self.set_rec(2 * node, l, mid, i, x);
+        } else {
+            self.set_rec(2 * node + 1, mid, r, i, x);
+        }
+        self.pull(node);
+    }
+}
+
+impl<M: Monoid, A: Action<M>> FromIterator<M::Item> for SegmentTree<M, A> {
+    fn from_iter<I: IntoIterator<Item = M::Item>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+/// Numbers usable with the predefined monoids and actions.
+pub trait Num: Copy + PartialOrd + std::ops::Add<Output = Self> + std::ops::Mul<Output = Self> {
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn from_len(len: usize) -> Self;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {$(
+        impl Num for $t {
+            const ZERO: Self = 0 as $t;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn from_len(len: usize) -> Self {
+                len as $t
+            }
+        }
+    )*};
+}
+
+impl_num!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_num_float {
+    ($($t:ty),*) => {$(
+        impl Num for $t {
+            const ZERO: Self = 0.0;
+            const MIN: Self = <$t>::NEG_INFINITY;
+            const MAX: Self = <$t>::INFINITY;
+
+            fn from_len(len: usize) -> Self {
+                len as $t
+            }
+        }
+    )*};
+}
+
+impl_num_float!(f32, f64);
+
+pub struct Sum<T>(PhantomData<T>);
+pub struct Min<T>(PhantomData<T>);
+pub struct Max<T>(PhantomData<T>);
+
+impl<T: Num> Monoid for Sum<T> {
+    type Item = T;
+
+    fn identity() -> T {
+        T::ZERO
+    }
+
+    fn op(a: &T, b: &T) -> T {
+        *a + *b
+    }
+}
+
+impl<T: Num> Monoid for Min<T> {
+    type Item = T;
+
+    fn identity() -> T {
+        T::MAX
+    }
+
+    fn op(a: &T, b: &T) -> T {
+        if b < a {
+            *b
+        } else {
+            *a
+        }
+    }
+}
+
+impl<T: Num> Monoid for Max<T> {
+    type Item = T;
+
+    fn identity() -> T {
+        T::MIN
+    }
+
+    fn op(a: &T, b: &T) -> T {
+        if b > a {
+            *b
+        } else {
+            *a
+        }
+    }
+}
+
+/// Adds a value to every element of a range.
+pub struct Add<T>(PhantomData<T>);
+/// Assigns a value to every element of a range.
+pub struct Assign<T>(PhantomData<T>);
+/// Point updates only, range updates are no-ops.
+pub struct NoUpdate;
+
+impl<T: Num> Action<Sum<T>> for Add<T> {
+    type Update = T;
+
+    fn apply(f: &T, x: &T, len: usize) -> T {
+        *x + *f * T::from_len(len)
+    }
+
+    fn compose(newer: &T, older: &T) -> T {
+        *newer + *older
+    }
+}
+
+impl<T: Num> Action<Sum<T>> for Assign<T> {
+    type Update = T;
+
+    fn apply(f: &T, _x: &T, len: usize) -> T {
+        *f * T::from_len(len)
+    }
+
+    fn compose(newer: &T, _older: &T) -> T {
+        *newer
+    }
+}
+
+macro_rules! impl_extremum_actions {
+    ($($m:ident),*) => {$(
+        impl<T: Num> Action<$m<T>> for Add<T> {
+            type Update = T;
+
+            fn apply(f: &T, x: &T, _len: usize) -> T {
+                *x + *f
+            }
+
+            fn compose(newer: &T, older: &T) -> T {
+                *newer + *older
+            }
+        }
+
+        impl<T: Num> Action<$m<T>> for Assign<T> {
+            type Update = T;
+
+            fn apply(f: &T, _x: &T, _len: usize) -> T {
+                *f
+            }
+
+            fn compose(newer: &T, _older: &T) -> T {
+                *newer
+            }
+        }
+    )*};
+}
+
+impl_extremum_actions!(Min, Max);
+
+impl<M: Monoid> Action<M> for NoUpdate {
+    type Update = ();
+
+    fn apply(_f: &(), x: &M::Item, _len: usize) -> M::Item {
+        x.clone()
+    }
+
+    fn compose(_newer: &(), _older: &()) {}
+}
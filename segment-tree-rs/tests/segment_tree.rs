@@ -0,0 +1,140 @@
+//! Random range updates and queries against a plain array updated and
+//! folded element by element, for every predefined monoid and action.
+
+use std::ops::Range;
+
+use segment_tree::{Action, Add, Assign, Max, Min, Monoid, NoUpdate, SegmentTree, Sum};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn value(&mut self) -> i64 {
+        self.below(2001) as i64 - 1000
+    }
+
+    /// Random range within `0..len`, empty ones included.
+    fn range(&mut self, len: usize) -> Range<usize> {
+        let a = self.below(len as u64 + 1) as usize;
+        let b = self.below(len as u64 + 1) as usize;
+        a.min(b)..a.max(b)
+    }
+}
+
+/// Runs random operations on a tree and on an array, `update` applies
+/// an update to one array element.
+fn check<M, A>(update: fn(&mut i64, i64))
+where
+    M: Monoid<Item = i64>,
+    A: Action<M, Update = i64>,
+{
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for len in (0..70).chain([127, 128, 129, 1000]) {
+        let mut model: Vec<i64> = (0..len).map(|_| rng.value()).collect();
+        let mut tree: SegmentTree<M, A> = model.iter().copied().collect();
+        assert_eq!(tree.len(), len);
+        assert_eq!(tree.is_empty(), len == 0);
+        for _ in 0..300 {
+            match rng.below(3) {
+                0 => {
+                    let r = rng.range(len);
+                    let f = rng.value();
+                    tree.update(r.clone(), f);
+                    model[r].iter_mut().for_each(|x| update(x, f));
+                }
+                1 if len > 0 => {
+                    let i = rng.below(len as u64) as usize;
+                    let x = rng.value();
+                    tree.set(i, x);
+                    model[i] = x;
+                }
+                _ => {
+                    let r = rng.range(len);
+                    let expected = model[r.clone()]
+                        .iter()
+                        .fold(M::identity(), |acc, x| M::op(&acc, x));
+                    assert_eq!(tree.query(r.clone()), expected, "range {:?}", r);
+                }
+            }
+        }
+        for (i, x) in model.iter().enumerate() {
+            assert_eq!(tree.get(i), *x);
+        }
+        let all = model.iter().fold(M::identity(), |acc, x| M::op(&acc, x));
+        assert_eq!(tree.query(..), all);
+    }
+}
+
+#[test]
+fn sum_add() {
+    check::<Sum<i64>, Add<i64>>(|x, f| *x += f);
+}
+
+#[test]
+fn sum_assign() {
+    check::<Sum<i64>, Assign<i64>>(|x, f| *x = f);
+}
+
+#[test]
+fn min_add() {
+    check::<Min<i64>, Add<i64>>(|x, f| *x += f);
+}
+
+#[test]
+fn min_assign() {
+    check::<Min<i64>, Assign<i64>>(|x, f| *x = f);
+}
+
+#[test]
+fn max_add() {
+    check::<Max<i64>, Add<i64>>(|x, f| *x += f);
+}
+
+#[test]
+fn max_assign() {
+    check::<Max<i64>, Assign<i64>>(|x, f| *x = f);
+}
+
+#[test]
+fn point_updates_only() {
+    let mut tree: SegmentTree<Sum<i64>, NoUpdate> = SegmentTree::new(10);
+    assert_eq!(tree.query(..), 0);
+    tree.update(.., ());
+    for i in 0..10 {
+        tree.set(i, i as i64);
+    }
+    assert_eq!(tree.query(..), 45);
+    assert_eq!(tree.query(3..=5), 12);
+    assert_eq!(tree.query(7..), 24);
+    assert_eq!(tree.query(..2), 1);
+    assert_eq!(tree.query(4..4), 0);
+}
+
+#[test]
+fn update_order_matters_for_assign() {
+    let mut tree: SegmentTree<Sum<i64>, Assign<i64>> = SegmentTree::new(8);
+    tree.update(0..8, 1);
+    tree.update(2..6, 5);
+    tree.update(4..8, 2);
+    // [1, 1, 5, 5, 2, 2, 2, 2]
+    assert_eq!(tree.query(..), 20);
+    assert_eq!(tree.query(3..5), 7);
+    assert_eq!(tree.get(1), 1);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn query_past_end() {
+    let tree: SegmentTree<Sum<i64>, Add<i64>> = SegmentTree::new(5);
+    tree.query(2..6);
+}
@@ -0,0 +1,412 @@
+//! Doubly linked list in a vector of slots, linked by indices.
+//!
+//! Nodes live in the slots of one vector and point to their neighbours
+//! by slot index instead of by pointer, so there is no unsafe code, and
+//! nodes are close together in memory. Removed nodes' slots are chained
+//! into a free list and reused by later inserts.
+//!
+//! Inserting returns a handle to the node: its slot index and the slot's
+//! generation, which is bumped every time the slot is freed. A handle to
+//! a removed node no longer matches its slot, even after the slot is
+//! reused, and operations given it find nothing instead of touching the
+//! wrong node. Any node can be reached, removed or moved by its handle
+//! in O(1), e.g. to keep entries of an LRU cache in order of use.
+//!
+//! See https://en.wikipedia.org/wiki/Doubly_linked_list
+//! and https://docs.rs/slotmap/latest/slotmap/
+
+use std::fmt;
+use std::iter::FusedIterator;
+
+/// Handle to a node of a `SlotList`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: usize,
+    generation: u32,
+}
+
+struct Node<T> {
+    value: T,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+enum State<T> {
+    Occupied(Node<T>),
+    Free { next_free: Option<usize> },
+}
+
+struct Slot<T> {
+    /// Wraps around, a handle would have to outlive 2^32 reuses of its
+    /// slot to be confused with a newer node.
+    generation: u32,
+    state: State<T>,
+}
+
+pub struct SlotList<T> {
+    slots: Vec<Slot<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    free: Option<usize>,
+    len: usize,
+}
+
+impl<T> Default for SlotList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SlotList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(cap: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(cap),
+            head: None,
+            tail: None,
+            free: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Node of a valid handle.
+    fn get_node(&self, h: Handle) -> Option<&Node<T>> {
+        match self.slots.get(h.index) {
+            Some(Slot {
+                generation,
+                state: State::Occupied(node),
+            }) if *generation == h.generation => Some(node),
+            _ => None,
+        }
+    }
+
+    /// Node in a slot known to be occupied.
+    fn node(&self, i: usize) -> &Node<T> {
+        match &self.slots[i].state {
+            State::Occupied(node) => node,
+            State::Free { .. } => unreachable!("free slot linked into list"),
+        }
+    }
+
+    fn node_mut(&mut self, i: usize) -> &mut Node<T> {
+        match &mut self.slots[i].state {
+            State::Occupied(node) => node,
+            State::Free { .. } => unreachable!("free slot linked into list"),
+        }
+    }
+
+    fn handle(&self, i: usize) -> Handle {
+        Handle {
+            index: i,
+            generation: self.slots[i].generation,
+        }
+    }
+
+    /// Index of the node of `h`, panics if `h` is stale.
+    fn index(&self, h: Handle) -> usize {
+        assert!(self.contains(h), "stale handle {:?}", h);
+        h.index
+    }
+
+    pub fn contains(&self, h: Handle) -> bool {
+        self.get_node(h).is_some()
+    }
+
+    pub fn get(&self, h: Handle) -> Option<&T> {
+        self.get_node(h).map(|n| &n.value)
+    }
+
+    pub fn get_mut(&mut self, h: Handle) -> Option<&mut T> {
+        if !self.contains(h) {
+            return None;
+        }
+        Some(&mut self.node_mut(h.index).value)
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|i| &self.node(i).value)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.tail.map(|i| &self.node(i).value)
+    }
+
+    pub fn front_handle(&self) -> Option<Handle> {
+        self.head.map(|i| self.handle(i))
+    }
+
+    pub fn back_handle(&self) -> Option<Handle> {
+        self.tail.map(|i| self.handle(i))
+    }
+
+    /// Handle of the node after the one of `h`.
+    pub fn next(&self, h: Handle) -> Option<Handle> {
+        self.get_node(h)?.next.map(|i| self.handle(i))
+    }
+
+    /// Handle of the node before the one of `h`.
+    pub fn prev(&self, h: Handle) -> Option<Handle> {
+        self.get_node(h)?.prev.map(|i| self.handle(i))
+    }
+
+    /// Puts `value` into a free slot, unlinked.
+    fn alloc(&mut self, value: T) -> usize {
+        let node = State::Occupied(Node {
+            value,
+            prev: None,
+            next: None,
+        });
+        match self.free {
+            Some(i) => {
+                let State::Free { next_free } = self.slots[i].state else {
+                    unreachable!("occupied slot in free list");
+                };
+                self.free = next_free;
+                self.slots[i].state = node;
+                i
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    state: node,
+                });
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    /// Links unlinked node `i` between `prev` and `next`, which must be
+    /// neighbours, or the ends of the list if `None`.
+    fn link(&mut self, i: usize, prev: Option<usize>, next: Option<usize>) {
+        let node = self.node_mut(i);
+        node.prev = prev;
+        node.next = next;
+        match prev {
+            Some(p) => self.node_mut(p).next = Some(i),
+            None => self.head = Some(i),
+        }
+        match next {
+            Some(n) => self.node_mut(n).prev = Some(i),
+            None => self.tail = Some(i),
+        }
+        self.len += 1;
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let Node { prev, next, .. } = *self.node(i);
+        match prev {
+            Some(p) => self.node_mut(p).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.node_mut(n).prev = prev,
+            None => self.tail = prev,
+        }
+        self.len -= 1;
+    }
+
+    fn insert(&mut self, value: T, prev: Option<usize>, next: Option<usize>) -> Handle {
+        let i = self.alloc(value);
+        self.link(i, prev, next);
+        self.handle(i)
+    }
+
+    pub fn push_front(&mut self, value: T) -> Handle {
+        self.insert(value, None, self.head)
+    }
+
+    pub fn push_back(&mut self, value: T) -> Handle {
+        self.insert(value, self.tail, None)
+    }
+
+    /// Inserts `value` before the node of `h`. Panics if `h` is stale.
+    pub fn insert_before(&mut self, h: Handle, value: T) -> Handle {
+        let next = self.index(h);
+        self.insert(value, self.node(next).prev, Some(next))
+    }
+
+    /// Inserts `value` after the node of `h`. Panics if `h` is stale.
+    pub fn insert_after(&mut self, h: Handle, value: T) -> Handle {
+        let prev = self.index(h);
+        self.insert(value, Some(prev), self.node(prev).next)
+    }
+
+    /// Removes the node of `h`, `None` if `h` is stale.
+    pub fn remove(&mut self, h: Handle) -> Option<T> {
+        if !self.contains(h) {
+            return None;
+        }
+        let i = h.index;
+        self.unlink(i);
+        let slot = &mut self.slots[i];
+        slot.generation = slot.generation.wrapping_add(1);
+        let state = std::mem::replace(
+            &mut slot.state,
+            State::Free {
+                next_free: self.free,
+            },
+        );
+        self.free = Some(i);
+        match state {
+            State::Occupied(node) => Some(node.value),
+            State::Free { .. } => unreachable!(),
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let h = self.front_handle()?;
+        self.remove(h)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let h = self.back_handle()?;
+        self.remove(h)
+    }
+
+    /// Moves the node of `h` to the front. Panics if `h` is stale.
+    pub fn move_to_front(&mut self, h: Handle) {
+        let i = self.index(h);
+        if self.head != Some(i) {
+            self.unlink(i);
+            self.link(i, None, self.head);
+        }
+    }
+
+    /// Moves the node of `h` to the back. Panics if `h` is stale.
+    pub fn move_to_back(&mut self, h: Handle) {
+        let i = self.index(h);
+        if self.tail != Some(i) {
+            self.unlink(i);
+            self.link(i, self.tail, None);
+        }
+    }
+
+    /// Removes all nodes. Existing handles become stale.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            front: self.head,
+            back: self.tail,
+            len: self.len,
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    list: &'a SlotList<T>,
+    front: Option<usize>,
+    back: Option<usize>,
+    /// Nodes between `front` and `back`, inclusive.
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.node(self.front?);
+        self.front = node.next;
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.node(self.back?);
+        self.back = node.prev;
+        self.len -= 1;
+        Some(&node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a SlotList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T>(SlotList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for SlotList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T> Extend<T> for SlotList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push_back(x);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for SlotList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlotList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
@@ -0,0 +1,154 @@
+//! Handles of removed nodes stay stale after their slots are reused, and
+//! random edits by handle against a `Vec` of handles and values.
+
+use slot_list::{Handle, SlotList};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn assert_stale(list: &mut SlotList<u32>, h: Handle) {
+    assert!(!list.contains(h));
+    assert_eq!(list.get(h), None);
+    assert_eq!(list.get_mut(h), None);
+    assert_eq!(list.next(h), None);
+    assert_eq!(list.prev(h), None);
+    let len = list.len();
+    assert_eq!(list.remove(h), None);
+    assert_eq!(list.len(), len);
+}
+
+#[test]
+fn stale_handle_after_slot_reuse() {
+    let mut list = SlotList::new();
+    let a = list.push_back(1);
+    let b = list.push_back(2);
+    let c = list.push_back(3);
+    assert_eq!(list.remove(b), Some(2));
+    assert_stale(&mut list, b);
+    // the freed slot is reused by the next insert
+    let d = list.push_front(4);
+    assert_ne!(d, b);
+    assert_stale(&mut list, b);
+    assert_eq!(list.get(d), Some(&4));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [4, 1, 3]);
+
+    // reused over and over, every old handle stays stale
+    let mut old = vec![b];
+    let (mut h, mut v) = (d, 4);
+    for i in 0..100 {
+        assert_eq!(list.remove(h), Some(v));
+        old.push(h);
+        (h, v) = (list.insert_after(a, i), i);
+        for &o in &old {
+            assert_stale(&mut list, o);
+        }
+    }
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.next(a), Some(h));
+    assert_eq!(list.prev(c), Some(h));
+
+    // clear makes every handle stale
+    list.clear();
+    for x in [a, c, h] {
+        assert_stale(&mut list, x);
+    }
+    let e = list.push_back(5);
+    assert_stale(&mut list, a);
+    assert_eq!(list.front_handle(), Some(e));
+}
+
+#[test]
+#[should_panic(expected = "stale handle")]
+fn move_stale_handle() {
+    let mut list = SlotList::new();
+    let a = list.push_back(1);
+    list.remove(a);
+    list.push_back(2);
+    list.move_to_front(a);
+}
+
+#[test]
+#[should_panic(expected = "stale handle")]
+fn insert_at_stale_handle() {
+    let mut list = SlotList::new();
+    let a = list.push_back(1);
+    list.pop_back();
+    list.push_back(2);
+    list.insert_before(a, 3);
+}
+
+#[test]
+fn random_edits() {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    let mut list = SlotList::new();
+    let mut model: Vec<(Handle, u32)> = Vec::new();
+    let mut removed = Vec::new();
+    for step in 0..20_000 {
+        let value = step as u32;
+        let pick = |rng: &mut Rng, len: usize| rng.below(len as u64) as usize;
+        match rng.below(9) {
+            0 => model.insert(0, (list.push_front(value), value)),
+            1 => model.push((list.push_back(value), value)),
+            2 if !model.is_empty() => {
+                let i = pick(&mut rng, model.len());
+                let h = list.insert_before(model[i].0, value);
+                model.insert(i, (h, value));
+            }
+            3 if !model.is_empty() => {
+                let i = pick(&mut rng, model.len());
+                let h = list.insert_after(model[i].0, value);
+                model.insert(i + 1, (h, value));
+            }
+            4 | 5 if !model.is_empty() => {
+                let (h, v) = model.remove(pick(&mut rng, model.len()));
+                assert_eq!(list.remove(h), Some(v));
+                removed.push(h);
+            }
+            6 if !model.is_empty() => {
+                let i = pick(&mut rng, model.len());
+                list.move_to_front(model[i].0);
+                let x = model.remove(i);
+                model.insert(0, x);
+            }
+            7 if !model.is_empty() => {
+                let i = pick(&mut rng, model.len());
+                list.move_to_back(model[i].0);
+                let x = model.remove(i);
+                model.push(x);
+            }
+            8 if !model.is_empty() => {
+                let i = pick(&mut rng, model.len());
+                *list.get_mut(model[i].0).unwrap() += 1;
+                model[i].1 += 1;
+            }
+            _ => {}
+        }
+        if step % 1000 == 0 {
+            assert_eq!(list.len(), model.len());
+            assert!(list.iter().eq(model.iter().map(|(_, v)| v)));
+            assert!(list.iter().rev().eq(model.iter().rev().map(|(_, v)| v)));
+            for (i, &(h, v)) in model.iter().enumerate() {
+                assert_eq!(list.get(h), Some(&v));
+                assert_eq!(list.prev(h), i.checked_sub(1).map(|j| model[j].0));
+                assert_eq!(list.next(h), model.get(i + 1).map(|x| x.0));
+            }
+            for &h in &removed {
+                assert!(!list.contains(h));
+            }
+        }
+    }
+    let values: Vec<u32> = model.iter().map(|x| x.1).collect();
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), values);
+}
@@ -0,0 +1,75 @@
+//! Times sorts of this crate against std on inputs of various shapes.
+//!
+//! Usage: cargo bench -p sort [-- LEN]
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+type Sort = fn(&mut [u64]);
+
+const SORTS: &[(&str, Sort)] = &[
+    ("std stable", <[u64]>::sort),
+    ("std unstable", <[u64]>::sort_unstable),
+    ("merge", sort::merge_sort),
+    ("quick", sort::quick_sort),
+    ("heap", sort::heap_sort),
+    ("pdq", sort::pdq_sort),
+    ("radix", sort::radix_sort),
+];
+
+const RUNS: usize = 3;
+
+/// xorshift64, good enough for shuffling
+fn random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn inputs(len: usize) -> Vec<(&'static str, Vec<u64>)> {
+    let mut state = 0x2545f4914f6cdd1d;
+    let random_input: Vec<u64> = (0..len).map(|_| random(&mut state)).collect();
+    let few_unique = random_input.iter().map(|x| x % 16).collect();
+    let mut sorted = random_input.clone();
+    sorted.sort_unstable();
+    let reversed = sorted.iter().rev().copied().collect();
+    let sawtooth = (0..len as u64).map(|i| i % 1000).collect();
+    vec![
+        ("random", random_input),
+        ("few unique", few_unique),
+        ("sorted", sorted),
+        ("reversed", reversed),
+        ("sawtooth", sawtooth),
+    ]
+}
+
+/// Best time of several runs.
+fn time(sort: Sort, input: &[u64]) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let mut v = input.to_vec();
+            let start = Instant::now();
+            sort(black_box(&mut v));
+            let elapsed = start.elapsed();
+            assert!(v.windows(2).all(|w| w[0] <= w[1]), "output is not sorted");
+            elapsed
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let len = std::env::args()
+        .skip(1)
+        .find_map(|a| a.parse().ok())
+        .unwrap_or(1_000_000);
+    println!("sorting {} u64s, best of {} runs", len, RUNS);
+    for (name, input) in inputs(len) {
+        println!("\n{}:", name);
+        for &(sort_name, sort) in SORTS {
+            let t = time(sort, &input);
+            println!("  {:<14} {:>10.2} ms", sort_name, t.as_secs_f64() * 1000.0);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! External merge sort for data that doesn't fit in memory.
+//!
+//! Input is cut into runs of bounded length, each run is sorted in memory
+//! with merge sort and written to a temporary file. The sorted output is
+//! then produced by k-way merging the run files, keeping the current head
+//! record of every run in a min-heap. Sort is stable.
+//!
+//! At most `fan_in` files are merged at once, to bound open files and
+//! buffer memory. With more runs than that, groups of consecutive runs
+//! are merged into longer runs in passes until few enough are left.
+//!
+//! See https://en.wikipedia.org/wiki/External_sorting
+
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::vec;
+
+use crate::heap::sift_down;
+use crate::merge::merge_sort_by;
+
+/// Values that can be written to run files and read back.
+pub trait Record: Sized {
+    fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()>;
+
+    /// Reads next record, `Ok(None)` at end of input.
+    fn read_from<R: Read>(r: &mut R) -> io::Result<Option<Self>>;
+}
+
+/// Fills `buf` completely, returns false if input ended before the
+/// first byte.
+fn read_exact_or_eof<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut n = 0;
+    while n < buf.len() {
+        match r.read(&mut buf[n..]) {
+            Ok(0) if n == 0 => return Ok(false),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(k) => n += k,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+macro_rules! impl_record {
+    ($($t:ty),*) => {$(
+        impl Record for $t {
+            fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+                w.write_all(&self.to_le_bytes())
+            }
+
+            fn read_from<R: Read>(r: &mut R) -> io::Result<Option<Self>> {
+                let mut buf = [0; std::mem::size_of::<$t>()];
+                Ok(read_exact_or_eof(r, &mut buf)?.then(|| <$t>::from_le_bytes(buf)))
+            }
+        }
+    )*};
+}
+
+impl_record!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// Length-prefixed bytes.
+impl Record for Vec<u8> {
+    fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        (self.len() as u64).write_to(w)?;
+        w.write_all(self)
+    }
+
+    fn read_from<R: Read>(r: &mut R) -> io::Result<Option<Self>> {
+        let Some(len) = u64::read_from(r)? else {
+            return Ok(None);
+        };
+        let mut buf = vec![0; len as usize];
+        r.read_exact(&mut buf)?;
+        Ok(Some(buf))
+    }
+}
+
+impl Record for String {
+    fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        (self.len() as u64).write_to(w)?;
+        w.write_all(self.as_bytes())
+    }
+
+    fn read_from<R: Read>(r: &mut R) -> io::Result<Option<Self>> {
+        match Vec::read_from(r)? {
+            Some(bytes) => String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Run file, removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    fn create(dir: &Path) -> io::Result<(Self, File)> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = dir.join(format!("extsort-{}-{}", process::id(), n));
+        let file = File::options().write(true).create_new(true).open(&path)?;
+        Ok((Self { path }, file))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+type OrdCmp<T> = fn(&T, &T) -> Ordering;
+
+pub struct ExternalSorter {
+    run_len: usize,
+    fan_in: usize,
+    dir: PathBuf,
+}
+
+impl Default for ExternalSorter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExternalSorter {
+    pub fn new() -> Self {
+        Self {
+            run_len: 1 << 20,
+            fan_in: 64,
+            dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Number of records sorted in memory at once, 1M by default. This
+    /// counts records, not bytes, so memory used depends on record size.
+    pub fn run_len(mut self, run_len: usize) -> Self {
+        assert!(run_len > 0, "run length must be positive");
+        self.run_len = run_len;
+        self
+    }
+
+    /// Maximum number of runs merged at once, 64 by default.
+    pub fn fan_in(mut self, fan_in: usize) -> Self {
+        assert!(fan_in >= 2, "fan-in must be at least 2");
+        self.fan_in = fan_in;
+        self
+    }
+
+    /// Directory for run files, system temp dir by default.
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    pub fn sort<T, I>(&self, input: I) -> io::Result<Sorted<T, OrdCmp<T>>>
+    where
+        T: Record + Ord,
+        I: IntoIterator<Item = T>,
+    {
+        self.sort_by(input, T::cmp as OrdCmp<T>)
+    }
+
+    pub fn sort_by<T, I, F>(&self, input: I, mut cmp: F) -> io::Result<Sorted<T, F>>
+    where
+        T: Record,
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut input = input.into_iter();
+        let mut files = Vec::new();
+        loop {
+            let mut run: Vec<T> = input.by_ref().take(self.run_len).collect();
+            merge_sort_by(&mut run, &mut cmp);
+            if files.is_empty() && run.len() < self.run_len {
+                // everything fit in memory
+                return Ok(Sorted {
+                    inner: Inner::Memory(run.into_iter()),
+                });
+            }
+            if run.is_empty() {
+                break;
+            }
+            files.push(self.write_run(run.into_iter().map(Ok))?);
+        }
+
+        while files.len() > self.fan_in {
+            // consecutive runs stay in order, which keeps the sort stable
+            let mut merged = Vec::with_capacity(files.len().div_ceil(self.fan_in));
+            let mut rest = files.into_iter();
+            loop {
+                let group: Vec<TempFile> = rest.by_ref().take(self.fan_in).collect();
+                match group.len() {
+                    0 => break,
+                    1 => merged.extend(group),
+                    _ => merged.push(self.write_run(Merge::new(group, &mut cmp)?)?),
+                }
+            }
+            files = merged;
+        }
+        Ok(Sorted {
+            inner: Inner::Merge(Merge::new(files, cmp)?),
+        })
+    }
+
+    fn write_run<T: Record>(
+        &self,
+        run: impl Iterator<Item = io::Result<T>>,
+    ) -> io::Result<TempFile> {
+        let (temp, file) = TempFile::create(&self.dir)?;
+        let mut w = BufWriter::new(file);
+        for x in run {
+            x?.write_to(&mut w)?;
+        }
+        w.flush()?;
+        Ok(temp)
+    }
+}
+
+struct Merge<T, F> {
+    runs: Vec<BufReader<File>>,
+    /// Min-heap of next record of each run with the run's index.
+    heads: Vec<(T, usize)>,
+    cmp: F,
+    _files: Vec<TempFile>,
+}
+
+impl<T, F> Merge<T, F>
+where
+    T: Record,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    /// Opens `files` for merging, they are removed once it's dropped.
+    fn new(files: Vec<TempFile>, cmp: F) -> io::Result<Self> {
+        let mut runs = Vec::with_capacity(files.len());
+        let mut heads = Vec::with_capacity(files.len());
+        for (i, temp) in files.iter().enumerate() {
+            let mut r = BufReader::new(File::open(&temp.path)?);
+            if let Some(x) = T::read_from(&mut r)? {
+                heads.push((x, i));
+            }
+            runs.push(r);
+        }
+        let mut merge = Self {
+            runs,
+            heads,
+            cmp,
+            _files: files,
+        };
+        for i in (0..merge.heads.len() / 2).rev() {
+            merge.sift_down(i);
+        }
+        Ok(merge)
+    }
+
+    fn sift_down(&mut self, i: usize) {
+        let cmp = &mut self.cmp;
+        // max-heap of reversed order, ties go to earlier runs for stability
+        sift_down(&mut self.heads, i, &mut |a: &(T, usize), b: &(T, usize)| {
+            cmp(&b.0, &a.0).then(b.1.cmp(&a.1))
+        });
+    }
+}
+
+impl<T, F> Iterator for Merge<T, F>
+where
+    T: Record,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<io::Result<T>> {
+        if self.heads.is_empty() {
+            return None;
+        }
+        let run = self.heads[0].1;
+        let x = match T::read_from(&mut self.runs[run]) {
+            Ok(Some(next)) => std::mem::replace(&mut self.heads[0].0, next),
+            Ok(None) => self.heads.swap_remove(0).0,
+            Err(e) => {
+                self.heads.clear();
+                return Some(Err(e));
+            }
+        };
+        self.sift_down(0);
+        Some(Ok(x))
+    }
+}
+
+enum Inner<T, F> {
+    Memory(vec::IntoIter<T>),
+    Merge(Merge<T, F>),
+}
+
+/// Sorted records. Run files are removed once this is dropped.
+pub struct Sorted<T, F> {
+    inner: Inner<T, F>,
+}
+
+impl<T, F> Iterator for Sorted<T, F>
+where
+    T: Record,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<io::Result<T>> {
+        match &mut self.inner {
+            Inner::Memory(it) => it.next().map(Ok),
+            Inner::Merge(merge) => merge.next(),
+        }
+    }
+}
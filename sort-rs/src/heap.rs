@@ -0,0 +1,47 @@
+//! Heapsort.
+//!
+//! Slice is turned into a max-heap in place, then the max is repeatedly
+//! swapped to the end and the heap shrunk by one. O(n log n) worst case
+//! with O(1) extra space, but not stable.
+//!
+//! See https://en.wikipedia.org/wiki/Heapsort
+
+use std::cmp::Ordering;
+
+pub fn heap_sort<T: Ord>(v: &mut [T]) {
+    heap_sort_by(v, T::cmp);
+}
+
+pub fn heap_sort_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in (0..v.len() / 2).rev() {
+        sift_down(v, i, &mut cmp);
+    }
+    for end in (1..v.len()).rev() {
+        v.swap(0, end);
+        sift_down(&mut v[..end], 0, &mut cmp);
+    }
+}
+
+/// Moves `v[i]` down until it's not less than its children.
+pub(crate) fn sift_down<T, F>(v: &mut [T], mut i: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        let mut child = 2 * i + 1;
+        if child >= v.len() {
+            break;
+        }
+        if child + 1 < v.len() && cmp(&v[child], &v[child + 1]) == Ordering::Less {
+            child += 1;
+        }
+        if cmp(&v[i], &v[child]) != Ordering::Less {
+            break;
+        }
+        v.swap(i, child);
+        i = child;
+    }
+}
@@ -0,0 +1,18 @@
+//! Sorting algorithms over slices.
+//!
+//! Every comparison sort comes in two forms: `xxx_sort` for `T: Ord` and
+//! `xxx_sort_by` taking a comparison function.
+
+mod external;
+mod heap;
+mod merge;
+mod pdq;
+mod quick;
+mod radix;
+
+pub use external::{ExternalSorter, Record, Sorted};
+pub use heap::{heap_sort, heap_sort_by};
+pub use merge::{merge_sort, merge_sort_by};
+pub use pdq::{pdq_sort, pdq_sort_by};
+pub use quick::{quick_sort, quick_sort_by};
+pub use radix::{counting_sort_by_key, msd_radix_sort, radix_sort, sort_by_key_radix, RadixKey};
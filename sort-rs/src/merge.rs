@@ -0,0 +1,96 @@
+//! Stable bottom-up merge sort.
+//!
+//! Runs of width 1, 2, 4, ... are merged pairwise until one run is left.
+//! To merge, the left run is moved out to a buffer and merged back with
+//! the right run into the slice. O(n log n) time, O(n) extra space.
+//!
+//! See https://en.wikipedia.org/wiki/Merge_sort#Bottom-up_implementation
+
+use std::cmp::Ordering;
+use std::{mem, ptr};
+
+pub fn merge_sort<T: Ord>(v: &mut [T]) {
+    merge_sort_by(v, T::cmp);
+}
+
+pub fn merge_sort_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    // order of zero-sized values is unobservable
+    if len < 2 || mem::size_of::<T>() == 0 {
+        return;
+    }
+    // only used as raw memory, its length stays 0 so that elements moved
+    // there aren't dropped twice
+    let mut buf: Vec<T> = Vec::with_capacity(len);
+    let mut width = 1;
+    while width < len {
+        let mut lo = 0;
+        while lo + width < len {
+            let hi = len.min(lo + 2 * width);
+            // SAFETY: buf can hold any run, since width < len
+            unsafe { merge(&mut v[lo..hi], width, buf.as_mut_ptr(), &mut cmp) };
+            lo = hi;
+        }
+        width *= 2;
+    }
+}
+
+/// Left part of the slice that was moved out to the buffer and is not
+/// yet merged back. There's a gap of exactly its length in the slice
+/// at `dest`, so dropping it while unwinding from a panicking `cmp` puts
+/// every element back into the slice.
+struct Hole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for Hole<T> {
+    fn drop(&mut self) {
+        // SAFETY: see struct doc
+        unsafe {
+            let len = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
+        }
+    }
+}
+
+/// Merges sorted `v[..mid]` and `v[mid..]`.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `mid` elements.
+unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let p = v.as_mut_ptr();
+    let end = p.add(v.len());
+    ptr::copy_nonoverlapping(p, buf, mid);
+    let mut left = Hole {
+        start: buf,
+        end: buf.add(mid),
+        dest: p,
+    };
+    let mut right = p.add(mid);
+
+    while left.start < left.end && right < end {
+        // on equal elements take from the left for stability
+        let src = if cmp(&*right, &*left.start) == Ordering::Less {
+            let r = right;
+            right = right.add(1);
+            r
+        } else {
+            let l = left.start;
+            left.start = left.start.add(1);
+            l
+        };
+        ptr::copy_nonoverlapping(src, left.dest, 1);
+        left.dest = left.dest.add(1);
+    }
+    // rest of the right run is in place already, rest of the left one
+    // gets copied back by drop
+}
@@ -0,0 +1,154 @@
+//! Pattern-defeating quicksort.
+//!
+//! Quicksort with the usual fixes for its weak spots:
+//! - short slices are finished with insertion sort;
+//! - pivot is the median of three, or of three medians of three for
+//!   longer slices;
+//! - partitioning is branchless, so mispredicted comparisons don't stall
+//!   the pipeline on random input;
+//! - an unbalanced partition makes the next one shuffle a few elements
+//!   around, which breaks up patterns that keep producing bad pivots;
+//! - if too many partitions were unbalanced anyway, the slice is sorted
+//!   with heapsort, so the worst case stays O(n log n);
+//! - if pivot equals the pivot of an ancestor partition bounding the
+//!   slice from the left, it's the slice's minimum, so elements equal to
+//!   it are split off in one pass, making many duplicates cheap.
+//!
+//! See https://arxiv.org/abs/2106.05123
+
+use std::cmp::Ordering;
+
+use crate::heap::heap_sort_by;
+use crate::quick::median_of_three;
+
+/// Slices up to this length are sorted with insertion sort.
+const INSERTION_THRESHOLD: usize = 24;
+/// Slices from this length choose pivot as median of medians.
+const NINTHER_THRESHOLD: usize = 128;
+
+pub fn pdq_sort<T: Ord>(v: &mut [T]) {
+    pdq_sort_by(v, T::cmp);
+}
+
+pub fn pdq_sort_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let limit = usize::BITS - v.len().leading_zeros();
+    sort(v, &mut cmp, None, limit);
+}
+
+/// `pred` is an element not greater than any in `v`, if known.
+/// `limit` is the number of unbalanced partitions left before
+/// switching to heapsort.
+fn sort<'a, T, F>(mut v: &'a mut [T], cmp: &mut F, mut pred: Option<&'a T>, mut limit: u32)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut balanced = true;
+    loop {
+        let len = v.len();
+        if len <= INSERTION_THRESHOLD {
+            insertion_sort(v, cmp);
+            return;
+        }
+        if limit == 0 {
+            heap_sort_by(v, &mut *cmp);
+            return;
+        }
+        if !balanced {
+            break_patterns(v);
+            limit -= 1;
+        }
+
+        let pivot = choose_pivot(v, cmp);
+        if let Some(p) = pred {
+            if cmp(p, &v[pivot]) != Ordering::Less {
+                let mid = partition(v, pivot, &mut |x, pivot| cmp(pivot, x) != Ordering::Less);
+                v = &mut v[mid + 1..];
+                continue;
+            }
+        }
+        let mid = partition(v, pivot, &mut |x, pivot| cmp(x, pivot) == Ordering::Less);
+        balanced = mid.min(len - mid) >= len / 8;
+
+        let (left, rest) = v.split_at_mut(mid);
+        let (pivot, right) = rest.split_first_mut().unwrap();
+        let pivot = &*pivot;
+        if left.len() < right.len() {
+            sort(left, cmp, pred, limit);
+            v = right;
+            pred = Some(pivot);
+        } else {
+            sort(right, cmp, Some(pivot), limit);
+            v = left;
+        }
+    }
+}
+
+/// Moves elements `x` for which `goes_left(x, pivot)` holds before the
+/// pivot and the rest after it, returns pivot's new position.
+///
+/// This is Lomuto's scheme without branches: the current element is
+/// always swapped to the boundary and the boundary moves on only if the
+/// element belongs to the left.
+fn partition<T, P>(v: &mut [T], pivot: usize, goes_left: &mut P) -> usize
+where
+    P: FnMut(&T, &T) -> bool,
+{
+    v.swap(0, pivot);
+    let (pivot, rest) = v.split_first_mut().unwrap();
+    let mut boundary = 0;
+    for i in 0..rest.len() {
+        let left = goes_left(&rest[i], pivot);
+        rest.swap(boundary, i);
+        boundary += left as usize;
+    }
+    v.swap(0, boundary);
+    boundary
+}
+
+fn choose_pivot<T, F>(v: &[T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    let (a, b, c) = (len / 4, len / 2, len / 4 * 3);
+    if len < NINTHER_THRESHOLD {
+        return median_of_three(v, (a, b, c), cmp);
+    }
+    let a = median_of_three(v, (a - 1, a, a + 1), cmp);
+    let b = median_of_three(v, (b - 1, b, b + 1), cmp);
+    let c = median_of_three(v, (c - 1, c, c + 1), cmp);
+    median_of_three(v, (a, b, c), cmp)
+}
+
+/// Swaps elements around pivot candidates with pseudo-random ones.
+fn break_patterns<T>(v: &mut [T]) {
+    let len = v.len();
+    let mut seed = len as u64 | 1;
+    let mut random = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % len as u64) as usize
+    };
+    for i in [len / 4, len / 2, len / 4 * 3] {
+        v.swap(i - 1, random());
+        v.swap(i, random());
+        v.swap(i + 1, random());
+    }
+}
+
+fn insertion_sort<T, F>(v: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && cmp(&v[j], &v[j - 1]) == Ordering::Less {
+            v.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Quicksort with 3-way partitioning.
+//!
+//! Elements are split into less than, equal to and greater than pivot,
+//! so inputs with many duplicates don't degrade to O(n^2). Pivot is the
+//! median of three elements at pseudo-random positions: fixed positions
+//! like first, middle and last get defeated by the way this partitioning
+//! reshuffles sorted input. Recursing into the smaller part only keeps
+//! the stack O(log n).
+//!
+//! See https://en.wikipedia.org/wiki/Quicksort#Repeated_elements
+
+use std::cmp::Ordering;
+
+pub fn quick_sort<T: Ord>(v: &mut [T]) {
+    quick_sort_by(v, T::cmp);
+}
+
+pub fn quick_sort_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut seed = v.len() as u64 | 1;
+    sort(v, &mut cmp, &mut seed);
+}
+
+fn sort<T, F>(mut v: &mut [T], cmp: &mut F, seed: &mut u64)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while v.len() > 1 {
+        let (lt, gt) = partition(v, cmp, seed);
+        let (left, rest) = v.split_at_mut(lt);
+        let right = &mut rest[gt - lt..];
+        if left.len() < right.len() {
+            sort(left, cmp, seed);
+            v = right;
+        } else {
+            sort(right, cmp, seed);
+            v = left;
+        }
+    }
+}
+
+/// xorshift64
+fn random(seed: &mut u64) -> u64 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 7;
+    *seed ^= *seed << 17;
+    *seed
+}
+
+pub(crate) fn median_of_three<T, F>(v: &[T], (a, b, c): (usize, usize, usize), cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let less = |cmp: &mut F, i: usize, j: usize| cmp(&v[i], &v[j]) == Ordering::Less;
+    if less(cmp, a, b) {
+        if less(cmp, b, c) {
+            b
+        } else if less(cmp, a, c) {
+            c
+        } else {
+            a
+        }
+    } else if less(cmp, a, c) {
+        a
+    } else if less(cmp, b, c) {
+        c
+    } else {
+        b
+    }
+}
+
+/// Partitions around pivot, returns `(lt, gt)` such that elements in
+/// `v[..lt]` are less than pivot, in `v[lt..gt]` equal and in `v[gt..]`
+/// greater.
+fn partition<T, F>(v: &mut [T], cmp: &mut F, seed: &mut u64) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len() as u64;
+    let mut pick = || (random(seed) % len) as usize;
+    let pivot = median_of_three(v, (pick(), pick(), pick()), cmp);
+    v.swap(0, pivot);
+    // pivot stays at 0 until the end, v[1..lt] < pivot,
+    // v[lt..i] == pivot, v[gt..] > pivot
+    let (mut lt, mut i, mut gt) = (1, 1, v.len());
+    while i < gt {
+        match cmp(&v[i], &v[0]) {
+            Ordering::Less => {
+                v.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                v.swap(i, gt);
+            }
+            Ordering::Equal => i += 1,
+        }
+    }
+    v.swap(0, lt - 1);
+    (lt - 1, gt)
+}
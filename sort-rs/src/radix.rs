@@ -0,0 +1,211 @@
+//! Non-comparison sorts: counting sort and radix sorts.
+//!
+//! Sorts taking a key function compute keys once, sort positions by key
+//! and then move elements into place following permutation cycles, so
+//! elements themselves needn't be `Copy` or `Clone`.
+//!
+//! See https://en.wikipedia.org/wiki/Counting_sort
+//! and https://en.wikipedia.org/wiki/Radix_sort
+
+/// Fixed-width keys that can be sorted a byte at a time.
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+
+    /// `i`-th byte, 0 being the least significant. Bytes must order
+    /// the same way as keys, so e.g. signed integers flip the sign bit.
+    fn byte(&self, i: usize) -> u8;
+}
+
+macro_rules! impl_radix_key {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn byte(&self, i: usize) -> u8 {
+                (*self >> (8 * i)) as u8
+            }
+        }
+    )*};
+}
+
+impl_radix_key!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn byte(&self, i: usize) -> u8 {
+                ((*self as $u ^ (1 << (<$u>::BITS - 1))) >> (8 * i)) as u8
+            }
+        }
+    )*};
+}
+
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// Moves `v[i]` to `v[dest[i]]` for every `i`. Clobbers `dest`.
+fn permute<T>(v: &mut [T], dest: &mut [usize]) {
+    for i in 0..v.len() {
+        while dest[i] != i {
+            let d = dest[i];
+            v.swap(i, d);
+            dest.swap(i, d);
+        }
+    }
+}
+
+/// Stable sort by integer keys less than `max_key`, O(n + max_key).
+pub fn counting_sort_by_key<T, F>(v: &mut [T], max_key: usize, mut key: F)
+where
+    F: FnMut(&T) -> usize,
+{
+    let keys: Vec<usize> = v.iter().map(&mut key).collect();
+    let mut start = vec![0; max_key + 1];
+    for &k in &keys {
+        assert!(k < max_key, "key {} is not less than {}", k, max_key);
+        start[k + 1] += 1;
+    }
+    for k in 1..start.len() {
+        start[k] += start[k - 1];
+    }
+    let mut dest: Vec<usize> = keys
+        .iter()
+        .map(|&k| {
+            start[k] += 1;
+            start[k] - 1
+        })
+        .collect();
+    permute(v, &mut dest);
+}
+
+/// One stable pass of LSD radix sort by byte `key` from `src` into `dst`.
+/// Returns false without moving anything if all keys share the byte.
+fn lsd_pass<T, F>(src: &[T], dst: &mut [T], key: F) -> bool
+where
+    T: Copy,
+    F: Fn(&T) -> u8,
+{
+    let mut start = [0usize; 257];
+    for x in src {
+        start[key(x) as usize + 1] += 1;
+    }
+    if start.contains(&src.len()) {
+        return false;
+    }
+    for b in 1..start.len() {
+        start[b] += start[b - 1];
+    }
+    for x in src {
+        let b = key(x) as usize;
+        dst[start[b]] = *x;
+        start[b] += 1;
+    }
+    true
+}
+
+/// LSD radix sort of keys themselves, O(n * K::BYTES).
+pub fn radix_sort<K: RadixKey>(v: &mut [K]) {
+    lsd_sort(v, |k| *k);
+}
+
+fn lsd_sort<T, K, F>(v: &mut [T], key: F)
+where
+    T: Copy,
+    K: RadixKey,
+    F: Fn(&T) -> K,
+{
+    if v.len() < 2 {
+        return;
+    }
+    let mut buf = v.to_vec();
+    // whether sorted data is currently in buf rather than v
+    let mut in_buf = false;
+    for i in 0..K::BYTES {
+        let moved = if in_buf {
+            lsd_pass(&buf, v, |x| key(x).byte(i))
+        } else {
+            lsd_pass(v, &mut buf, |x| key(x).byte(i))
+        };
+        in_buf ^= moved;
+    }
+    if in_buf {
+        v.copy_from_slice(&buf);
+    }
+}
+
+/// Stable LSD radix sort by keys extracted with `key`.
+pub fn sort_by_key_radix<T, K, F>(v: &mut [T], mut key: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    let mut keyed: Vec<(K, usize)> = v.iter().map(&mut key).zip(0..).collect();
+    lsd_sort(&mut keyed, |&(k, _)| k);
+    let mut dest = vec![0; v.len()];
+    for (i, &(_, from)) in keyed.iter().enumerate() {
+        dest[from] = i;
+    }
+    permute(v, &mut dest);
+}
+
+/// Slices shorter than this are finished with insertion sort.
+const MSD_CUTOFF: usize = 32;
+
+/// MSD radix sort of byte strings, in place (American flag sort).
+/// Not stable.
+pub fn msd_radix_sort<T: AsRef<[u8]>>(v: &mut [T]) {
+    // digit 0 means string ended, shorter strings go first
+    let digit = |s: &T, depth: usize| s.as_ref().get(depth).map_or(0, |&b| b as usize + 1);
+    let mut stack = vec![(0, v.len(), 0)];
+
+    while let Some((lo, hi, depth)) = stack.pop() {
+        let v = &mut v[lo..hi];
+        if v.len() < MSD_CUTOFF {
+            insertion_sort_suffixes(v, depth);
+            continue;
+        }
+        let mut count = [0usize; 257];
+        for s in v.iter() {
+            count[digit(s, depth)] += 1;
+        }
+        let mut head = [0usize; 257];
+        let mut tail = [0usize; 257];
+        let mut pos = 0;
+        for d in 0..257 {
+            head[d] = pos;
+            pos += count[d];
+            tail[d] = pos;
+        }
+        // swap each element straight into its bucket
+        for d in 0..257 {
+            while head[d] < tail[d] {
+                let e = digit(&v[head[d]], depth);
+                if e == d {
+                    head[d] += 1;
+                } else {
+                    v.swap(head[d], head[e]);
+                    head[e] += 1;
+                }
+            }
+        }
+        // bucket 0 holds equal strings, nothing to sort there
+        for d in 1..257 {
+            let start = tail[d] - count[d];
+            if count[d] > 1 {
+                stack.push((lo + start, lo + tail[d], depth + 1));
+            }
+        }
+    }
+}
+
+/// Sorts strings sharing their first `depth` bytes.
+fn insertion_sort_suffixes<T: AsRef<[u8]>>(v: &mut [T], depth: usize) {
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && v[j].as_ref()[depth..] < v[j - 1].as_ref()[depth..] {
+            v.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Property tests: sorts must agree with std's sort on random inputs.
+
+use std::cmp::Ordering;
+
+type Sort = fn(&mut [i32]);
+type SortBy = fn(&mut [(i32, usize)], &mut dyn FnMut(&(i32, usize), &(i32, usize)) -> Ordering);
+
+const SORTS: &[(&str, Sort)] = &[
+    ("merge", sort::merge_sort),
+    ("quick", sort::quick_sort),
+    ("heap", sort::heap_sort),
+    ("pdq", sort::pdq_sort),
+    ("radix", sort::radix_sort),
+];
+
+const STABLE_SORTS: &[(&str, SortBy)] = &[
+    ("merge", |v, cmp| sort::merge_sort_by(v, cmp)),
+    ("radix by key", |v, _| sort::sort_by_key_radix(v, |&(x, _)| x)),
+];
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Random inputs of all lengths up to some limit, with values drawn
+/// from ranges of various widths to get few or no duplicates.
+fn inputs() -> impl Iterator<Item = Vec<i32>> {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    (0..300).flat_map(move |len| {
+        [2, 16, 1000, u32::MAX as u64]
+            .map(|range| (0..len).map(|_| rng.below(range) as i32).collect())
+    })
+}
+
+#[test]
+fn matches_std() {
+    for input in inputs() {
+        let mut expected = input.clone();
+        expected.sort();
+        for (name, sort) in SORTS {
+            let mut v = input.clone();
+            sort(&mut v);
+            assert_eq!(v, expected, "{} sort of {:?}", name, input);
+        }
+    }
+}
+
+#[test]
+fn matches_std_on_patterns() {
+    let len = 10_000;
+    let patterns: Vec<Vec<i32>> = vec![
+        (0..len).collect(),
+        (0..len).rev().collect(),
+        vec![7; len as usize],
+        (0..len).map(|i| i % 100).collect(),
+        (0..len).map(|i| if i < len / 2 { i } else { len - i }).collect(),
+    ];
+    for input in patterns {
+        let mut expected = input.clone();
+        expected.sort();
+        for (name, sort) in SORTS {
+            let mut v = input.clone();
+            sort(&mut v);
+            assert!(v == expected, "{} sort failed on pattern", name);
+        }
+    }
+}
+
+#[test]
+fn stable() {
+    for input in inputs() {
+        // tag elements with original positions, compare by value only
+        let tagged: Vec<(i32, usize)> = input.into_iter().zip(0..).collect();
+        let mut expected = tagged.clone();
+        expected.sort_by_key(|&(x, _)| x);
+        for (name, sort) in STABLE_SORTS {
+            let mut v = tagged.clone();
+            sort(&mut v, &mut |a, b| a.0.cmp(&b.0));
+            assert_eq!(v, expected, "{} sort is not stable", name);
+        }
+    }
+}
+
+#[test]
+fn counting_stable() {
+    for input in inputs() {
+        // keys are the high halves, so equal keys are common
+        let key = |&(x, _): &(i32, usize)| (x as u32 >> 16) as usize;
+        let tagged: Vec<(i32, usize)> = input.into_iter().zip(0..).collect();
+        let mut expected = tagged.clone();
+        expected.sort_by_key(key);
+        let mut v = tagged;
+        sort::counting_sort_by_key(&mut v, 1 << 16, key);
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn msd_matches_std() {
+    let mut rng = Rng(7);
+    for len in 0..2000 {
+        // short strings over a small alphabet share lots of prefixes
+        let mut v: Vec<Vec<u8>> = (0..len % 300)
+            .map(|_| (0..rng.below(8)).map(|_| b'a' + rng.below(3) as u8).collect())
+            .collect();
+        if len % 2 == 1 {
+            v.iter_mut().for_each(|s| s.extend(rng.next().to_le_bytes()));
+        }
+        let mut expected = v.clone();
+        expected.sort();
+        sort::msd_radix_sort(&mut v);
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn panicking_cmp_keeps_elements() {
+    let mut rng = Rng(1);
+    let input: Vec<String> = (0..500).map(|_| rng.below(1000).to_string()).collect();
+    for limit in [0, 1, 10, 100, 1000] {
+        let mut v = input.clone();
+        let mut calls = 0;
+        let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            sort::merge_sort_by(&mut v, |a, b| {
+                calls += 1;
+                if calls > limit {
+                    panic!("cmp panicked");
+                }
+                a.cmp(b)
+            })
+        }));
+        assert!(r.is_err());
+        // every element is still there exactly once
+        let mut got = v.clone();
+        got.sort();
+        let mut expected = input.clone();
+        expected.sort();
+        assert_eq!(got, expected);
+    }
+}
+
+#[test]
+fn external_matches_std() {
+    let mut rng = Rng(3);
+    let dir = std::env::temp_dir().join(format!("extsort-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // in memory, exactly one run, several runs, several merge passes
+    for (len, run_len, fan_in) in [
+        (0, 5, 64),
+        (4, 5, 64),
+        (5, 5, 64),
+        (6, 5, 64),
+        (1000, 7, 64),
+        (10000, 1000, 64),
+        (1000, 7, 2),
+        (1000, 7, 3),
+        (1000, 10, 10),
+        (1001, 10, 10),
+    ] {
+        let sorter = sort::ExternalSorter::new().run_len(run_len).fan_in(fan_in).temp_dir(&dir);
+
+        // low half of a record is its position, compare high halves only
+        let input: Vec<u64> = (0..len).map(|i| rng.below(50) << 32 | i).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|x| x >> 32);
+        let sorted = sorter.sort_by(input, |a, b| (a >> 32).cmp(&(b >> 32))).unwrap();
+        let v: Vec<u64> = sorted.map(Result::unwrap).collect();
+        assert_eq!(v, expected, "external sort is not stable");
+
+        let input: Vec<String> = (0..len).map(|_| rng.next().to_string()).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        let v: Vec<String> = sorter.sort(input).unwrap().map(Result::unwrap).collect();
+        assert_eq!(v, expected);
+    }
+    // run files are gone
+    std::fs::remove_dir(&dir).unwrap();
+}
@@ -0,0 +1,127 @@
+//! Sparse table for static range queries.
+//!
+//! Row `k` of the table holds results for all ranges of length `2^k`, so
+//! any range is covered by two (possibly overlapping) ranges from the
+//! same row. Overlap is fine only if the operation is idempotent, which
+//! gives O(1) queries after O(n log n) build.
+//!
+//! See https://en.wikipedia.org/wiki/Range_minimum_query
+
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+/// Associative operation with `op(x, x) == x`.
+pub trait Idempotent {
+    type Item: Clone;
+
+    fn op(a: &Self::Item, b: &Self::Item) -> Self::Item;
+}
+
+pub struct SparseTable<O: Idempotent> {
+    table: Vec<Vec<O::Item>>,
+}
+
+impl<O: Idempotent> SparseTable<O> {
+    pub fn new(v: Vec<O::Item>) -> Self {
+        let mut table = vec![v];
+        let mut half = 1;
+        while 2 * half <= table[0].len() {
+            let prev = table.last().unwrap();
+            let row = (0..prev.len() - half)
+                .map(|i| O::op(&prev[i], &prev[i + half]))
+                .collect();
+            table.push(row);
+            half *= 2;
+        }
+        Self { table }
+    }
+
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Result of the operation over `range`, `None` if it's empty.
+    pub fn query(&self, range: impl RangeBounds<usize>) -> Option<O::Item> {
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(l <= r, "range start {} is greater than end {}", l, r);
+        assert!(r <= self.len(), "range end {} out of range for length {}", r, self.len());
+        if l == r {
+            return None;
+        }
+        let k = (r - l).ilog2() as usize;
+        let row = &self.table[k];
+        Some(O::op(&row[l], &row[r - (1 << k)]))
+    }
+}
+
+impl<O: Idempotent> FromIterator<O::Item> for SparseTable<O> {
+    fn from_iter<I: IntoIterator<Item = O::Item>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+pub struct Min<T>(PhantomData<T>);
+pub struct Max<T>(PhantomData<T>);
+pub struct Gcd<T>(PhantomData<T>);
+
+impl<T: Ord + Clone> Idempotent for Min<T> {
+    type Item = T;
+
+    fn op(a: &T, b: &T) -> T {
+        a.min(b).clone()
+    }
+}
+
+impl<T: Ord + Clone> Idempotent for Max<T> {
+    type Item = T;
+
+    fn op(a: &T, b: &T) -> T {
+        a.max(b).clone()
+    }
+}
+
+macro_rules! impl_gcd {
+    ($($t:ty),*) => {$(
+        impl Idempotent for Gcd<$t> {
+            type Item = $t;
+
+            /// Binary (Stein's) gcd.
+            fn op(&a: &$t, &b: &$t) -> $t {
+                let (mut a, mut b) = (a, b);
+                if a == 0 {
+                    return b;
+                }
+                if b == 0 {
+                    return a;
+                }
+                let shift = (a | b).trailing_zeros();
+                a >>= a.trailing_zeros();
+                loop {
+                    b >>= b.trailing_zeros();
+                    if a > b {
+                        std::mem::swap(&mut a, &mut b);
+                    }
+                    b -= a;
+                    if b == 0 {
+                        return a << shift;
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+impl_gcd!(u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,87 @@
+//! Queries over every range of random arrays against folding the range
+//! element by element.
+
+use sparse_table::{Gcd, Idempotent, Max, Min, SparseTable};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn check<O: Idempotent<Item = u64>>(value: fn(&mut Rng) -> u64) {
+    let mut rng = Rng(0x9e3779b97f4a7c15);
+    for len in (0..70).chain([127, 128, 129, 255, 256, 257]) {
+        let v: Vec<u64> = (0..len).map(|_| value(&mut rng)).collect();
+        let table: SparseTable<O> = v.iter().copied().collect();
+        assert_eq!(table.len(), len);
+        assert_eq!(table.is_empty(), len == 0);
+        for l in 0..=len {
+            assert_eq!(table.query(l..l), None);
+            let mut acc = None;
+            for r in l + 1..=len {
+                acc = Some(match acc {
+                    None => v[r - 1],
+                    Some(a) => O::op(&a, &v[r - 1]),
+                });
+                assert_eq!(table.query(l..r), acc, "range {}..{}", l, r);
+            }
+        }
+        if len > 0 {
+            assert_eq!(table.query(..), table.query(0..len));
+            assert_eq!(table.query(1..), table.query(1..len));
+            assert_eq!(table.query(..=len - 1), table.query(0..len));
+        }
+    }
+}
+
+#[test]
+fn min() {
+    check::<Min<u64>>(|rng| rng.below(1000));
+}
+
+#[test]
+fn max() {
+    check::<Max<u64>>(|rng| rng.below(1000));
+}
+
+#[test]
+fn gcd() {
+    // products of small primes, so that ranges share factors, and zeros
+    check::<Gcd<u64>>(|rng| {
+        [2, 3, 5, 7, 2, 2, 0]
+            .iter()
+            .take(1 + rng.below(7) as usize)
+            .filter(|_| rng.below(2) == 0)
+            .product()
+    });
+}
+
+#[test]
+fn gcd_against_euclid() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..10_000 {
+        let (a, b) = (rng.next() >> rng.below(64), rng.next() >> rng.below(64));
+        let (mut x, mut y) = (a, b);
+        while y != 0 {
+            (x, y) = (y, x % y);
+        }
+        assert_eq!(Gcd::<u64>::op(&a, &b), x, "gcd({}, {})", a, b);
+    }
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn query_past_end() {
+    let table: SparseTable<Min<u32>> = (0..5).collect();
+    table.query(2..6);
+}
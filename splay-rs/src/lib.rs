@@ -0,0 +1,342 @@
+//! Splay tree: a self-adjusting binary search tree.
+//!
+//! Every access splays the accessed node up to the root by rotations, so
+//! recently used values are cheap to reach again. There is no balance
+//! information and a single operation can take O(n), e.g. after inserting
+//! values in sorted order, which leaves the tree a path. But the rotations
+//! of the splay roughly halve the depth of every node on the way, so any
+//! sequence of m operations on a tree of n values takes O(m log n), and
+//! much less when the accesses are skewed towards a few values or go in
+//! order.
+//!
+//! Splaying is done top-down: on the way down, the nodes passed by are
+//! split off into a tree of smaller values and a tree of larger ones,
+//! which are joined under the accessed node at the end. That takes no
+//! recursion, so deep trees don't overflow the stack. Dropping is done in
+//! a loop for the same reason.
+//!
+//! See https://en.wikipedia.org/wiki/Splay_tree
+//! and https://www.cs.cmu.edu/~sleator/papers/self-adjusting.pdf
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T> Node<T> {
+    fn new(value: T) -> Box<Self> {
+        Box::new(Self {
+            value,
+            left: None,
+            right: None,
+        })
+    }
+}
+
+/// Splays the node where the search by `cmp` ends to the root: the node
+/// `cmp` returns Equal for, or else the last node on the search path.
+/// `cmp` tells whether the target is less or greater than a node's value.
+fn splay<T>(mut t: Box<Node<T>>, mut cmp: impl FnMut(&T) -> Ordering) -> Box<Node<T>> {
+    // nodes smaller than the target, each the right child of the previous
+    let mut lesser = Vec::new();
+    // nodes larger than the target, each the left child of the previous
+    let mut greater = Vec::new();
+    loop {
+        match cmp(&t.value) {
+            Ordering::Less => {
+                let Some(mut l) = t.left.take() else { break };
+                if cmp(&l.value) == Ordering::Less {
+                    // zig-zig: rotate right first
+                    t.left = l.right.take();
+                    l.right = Some(t);
+                    t = l;
+                    match t.left.take() {
+                        Some(ll) => l = ll,
+                        None => break,
+                    }
+                }
+                greater.push(t);
+                t = l;
+            }
+            Ordering::Greater => {
+                let Some(mut r) = t.right.take() else { break };
+                if cmp(&r.value) == Ordering::Greater {
+                    t.right = r.left.take();
+                    r.left = Some(t);
+                    t = r;
+                    match t.right.take() {
+                        Some(rr) => r = rr,
+                        None => break,
+                    }
+                }
+                lesser.push(t);
+                t = r;
+            }
+            Ordering::Equal => break,
+        }
+    }
+    let mut left = t.left.take();
+    for mut n in lesser.into_iter().rev() {
+        n.right = left;
+        left = Some(n);
+    }
+    let mut right = t.right.take();
+    for mut n in greater.into_iter().rev() {
+        n.left = right;
+        right = Some(n);
+    }
+    t.left = left;
+    t.right = right;
+    t
+}
+
+/// Ordered set.
+pub struct SplayTree<T> {
+    root: Link<T>,
+    len: usize,
+}
+
+impl<T> Default for SplayTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SplayTree<T> {
+    pub const fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of nodes on the longest path from the root, 0 if empty.
+    /// Takes O(n).
+    pub fn height(&self) -> usize {
+        let mut max = 0;
+        let mut stack: Vec<(&Node<T>, usize)> = self.root.iter().map(|n| (&**n, 1)).collect();
+        while let Some((node, depth)) = stack.pop() {
+            max = max.max(depth);
+            for child in [&node.left, &node.right].into_iter().flatten() {
+                stack.push((child, depth + 1));
+            }
+        }
+        max
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    fn splay_by(&mut self, cmp: impl FnMut(&T) -> Ordering) -> Option<&mut Box<Node<T>>> {
+        self.root = self.root.take().map(|t| splay(t, cmp));
+        self.root.as_mut()
+    }
+
+    /// Smallest value, splayed to the root.
+    pub fn first(&mut self) -> Option<&T> {
+        self.splay_by(|_| Ordering::Less).map(|n| &n.value)
+    }
+
+    /// Largest value, splayed to the root.
+    pub fn last(&mut self) -> Option<&T> {
+        self.splay_by(|_| Ordering::Greater).map(|n| &n.value)
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.splay_by(|_| Ordering::Less)?;
+        let mut node = self.root.take().unwrap();
+        self.root = node.right.take();
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.splay_by(|_| Ordering::Greater)?;
+        let mut node = self.root.take().unwrap();
+        self.root = node.left.take();
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    /// Values in ascending order, without splaying.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut it = Iter {
+            stack: Vec::new(),
+            len: self.len,
+        };
+        it.push_left(self.root.as_deref());
+        it
+    }
+}
+
+impl<T: Ord> SplayTree<T> {
+    fn splay_to<Q>(&mut self, key: &Q) -> Option<&mut Box<Node<T>>>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.splay_by(|v| key.cmp(v.borrow()))
+    }
+
+    /// Inserts `value`, false if an equal value is already present. The
+    /// value ends up at the root either way.
+    pub fn insert(&mut self, value: T) -> bool {
+        let Some(root) = self.splay_to(&value) else {
+            self.root = Some(Node::new(value));
+            self.len = 1;
+            return true;
+        };
+        let ord = value.cmp(&root.value);
+        if ord == Ordering::Equal {
+            return false;
+        }
+        let mut root = self.root.take().unwrap();
+        let mut node = Node::new(value);
+        if ord == Ordering::Less {
+            node.left = root.left.take();
+            node.right = Some(root);
+        } else {
+            node.right = root.right.take();
+            node.left = Some(root);
+        }
+        self.root = Some(node);
+        self.len += 1;
+        true
+    }
+
+    /// Removes and returns the value equal to `key`.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if key.cmp(self.splay_to(key)?.value.borrow()) != Ordering::Equal {
+            return None;
+        }
+        let mut root = self.root.take().unwrap();
+        // all of the left subtree is smaller than the key, so splaying it
+        // brings up its largest value, which has no right child
+        self.root = match root.left.take() {
+            Some(left) => {
+                let mut max = splay(left, |_| Ordering::Greater);
+                max.right = root.right.take();
+                Some(max)
+            }
+            None => root.right.take(),
+        };
+        self.len -= 1;
+        Some(root.value)
+    }
+
+    /// Value equal to `key`, splayed to the root. Takes `&mut self`, as
+    /// even unsuccessful searches restructure the tree.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let root = self.splay_to(key)?;
+        (key.cmp(root.value.borrow()) == Ordering::Equal).then_some(&root.value)
+    }
+
+    pub fn contains<Q>(&mut self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<T> Drop for SplayTree<T> {
+    fn drop(&mut self) {
+        // rotate left children up until there are none, then go right
+        let mut link = self.root.take();
+        while let Some(mut node) = link {
+            link = match node.left.take() {
+                Some(mut left) => {
+                    node.left = left.right.take();
+                    left.right = Some(node);
+                    Some(left)
+                }
+                None => node.right.take(),
+            };
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    /// Nodes whose value and right subtree are yet to be visited.
+    stack: Vec<&'a Node<T>>,
+    len: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        self.len -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a SplayTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Extend<T> for SplayTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SplayTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SplayTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self).finish()
+    }
+}
@@ -0,0 +1,166 @@
+//! Splay trees against std's BTreeSet, and amortized costs counted in
+//! comparisons.
+
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use splay::SplayTree;
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+thread_local! {
+    static CMPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Key counting its comparisons.
+#[derive(Debug, PartialEq, Eq)]
+struct Key(u32);
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        CMPS.with(|c| c.set(c.get() + 1));
+        self.0.cmp(&other.0)
+    }
+}
+
+/// Comparisons done by `f`.
+fn cmps(f: impl FnOnce()) -> usize {
+    CMPS.with(|c| c.set(0));
+    f();
+    CMPS.with(|c| c.get())
+}
+
+fn log2(n: usize) -> usize {
+    n.ilog2() as usize + 1
+}
+
+#[test]
+fn same_as_btreeset() {
+    let mut rng = Rng(1);
+    let mut tree = SplayTree::new();
+    let mut set = BTreeSet::new();
+    for _ in 0..100_000 {
+        let x = rng.next() % 1000;
+        match rng.next() % 4 {
+            0 | 1 => assert_eq!(tree.insert(x), set.insert(x)),
+            2 => assert_eq!(tree.remove(&x), set.take(&x)),
+            _ => assert_eq!(tree.get(&x), set.get(&x)),
+        }
+        assert_eq!(tree.len(), set.len());
+    }
+    assert!(tree.iter().eq(set.iter()));
+    assert_eq!(tree.first(), set.first());
+    assert_eq!(tree.last(), set.last());
+    while let Some(x) = tree.pop_first() {
+        assert_eq!(Some(x), set.pop_first());
+        assert_eq!(tree.pop_last(), set.pop_last());
+    }
+    assert!(set.is_empty() && tree.is_empty());
+}
+
+#[test]
+fn borrowed_lookup() {
+    let mut tree: SplayTree<String> = ["b", "a", "c"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(tree.get("a").map(String::as_str), Some("a"));
+    assert!(!tree.contains("d"));
+    assert_eq!(tree.remove("b").as_deref(), Some("b"));
+    assert_eq!(format!("{:?}", tree), r#"{"a", "c"}"#);
+}
+
+#[test]
+fn sorted_insert_makes_path() {
+    let n = 100_000;
+    let mut tree = SplayTree::new();
+    // each new maximum goes to the root with the old tree on its left
+    let c = cmps(|| tree.extend((0..n).map(Key)));
+    assert!(c <= 2 * n as usize, "{} comparisons", c);
+    assert_eq!(tree.height(), n as usize);
+    // a path this deep is dropped without overflowing the stack
+}
+
+#[test]
+fn deep_access_pays_for_itself() {
+    let n = 100_000;
+    let mut tree: SplayTree<Key> = (0..n).map(Key).collect();
+    // the first access to the bottom of the path takes O(n), but halves
+    // the depth of the path as it goes
+    assert!(tree.contains(&Key(0)));
+    assert!(tree.height() <= n as usize / 2 + 2, "height {}", tree.height());
+}
+
+#[test]
+fn random_access_is_logarithmic() {
+    let n: u32 = 1 << 16;
+    let m = 200_000;
+    let mut rng = Rng(7);
+    let mut tree: SplayTree<Key> = (0..n).map(Key).collect();
+    let c = cmps(|| {
+        for _ in 0..m {
+            let k = (rng.next() % n as u64) as u32;
+            assert!(tree.contains(&Key(k)));
+        }
+    });
+    // O(m log n + n log n), the second term paying for the initial path
+    let bound = 2 * (m + n as usize) * log2(n as usize);
+    assert!(c <= bound, "{} comparisons, bound {}", c, bound);
+}
+
+#[test]
+fn sequential_access_is_linear() {
+    let n = 100_000;
+    let mut tree: SplayTree<Key> = (0..n).map(Key).collect();
+    let mut rng = Rng(3);
+    // shuffle the shape first
+    for _ in 0..n {
+        tree.contains(&Key((rng.next() % n as u64) as u32));
+    }
+    // accessing all values in order takes O(n) in total
+    let c = cmps(|| {
+        for k in 0..n {
+            assert!(tree.contains(&Key(k)));
+        }
+    });
+    assert!(c <= 10 * n as usize, "{} comparisons", c);
+}
+
+#[test]
+fn working_set_is_cheap() {
+    let n: u32 = 1 << 16;
+    let hot = 16;
+    let m = 100_000;
+    let mut rng = Rng(5);
+    let mut tree: SplayTree<Key> = (0..n).map(|k| Key(k.wrapping_mul(2654435761) % n)).collect();
+    let mut keys = Vec::new();
+    while keys.len() < hot {
+        keys.push((rng.next() % n as u64) as u32);
+    }
+    for &k in &keys {
+        tree.contains(&Key(k));
+    }
+    // repeated accesses to a few values cost about log of their number,
+    // no matter the size of the tree
+    let c = cmps(|| {
+        for _ in 0..m {
+            let k = keys[(rng.next() % hot as u64) as usize];
+            assert!(tree.contains(&Key(k)));
+        }
+    });
+    let bound = 2 * m * log2(hot) + 2 * n as usize;
+    assert!(c <= bound, "{} comparisons, bound {}", c, bound);
+}
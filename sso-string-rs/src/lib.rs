@@ -0,0 +1,401 @@
+//! String with small string optimization.
+//!
+//! An `SsoString` is three words, like a `String`. Strings of up to 23
+//! bytes (on 64-bit) are stored inline in those words, with no
+//! allocation. Longer strings spill to the heap, where the three words
+//! are the pointer, length and capacity of a `String` buffer.
+//!
+//! The two forms are told apart by the last byte of the struct. Inline,
+//! it's the length, at most 23. On the heap, it's the byte of the
+//! capacity that is the last one in memory, and the capacity is stored
+//! with that byte set to 0xFF. This leaves the capacity one byte short of
+//! a word, so heap strings are limited to 2^56 bytes on 64-bit, and to
+//! 16 MiB on 32-bit.
+//!
+//! See https://cpp-optimizations.netlify.app/small_strings/
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::str;
+
+const WORD: usize = mem::size_of::<usize>();
+/// Max length of an inline string.
+const INLINE_CAP: usize = 3 * WORD - 1;
+const HEAP_TAG: usize = 0xFF;
+/// Max capacity of a heap string, the rest of the word is the tag.
+const MAX_CAP: usize = usize::MAX >> 8;
+/// Shifts of the tag and the capacity within the capacity word, putting
+/// the tag into its last byte in memory.
+const TAG_SHIFT: u32 = if cfg!(target_endian = "little") { usize::BITS - 8 } else { 0 };
+const CAP_SHIFT: u32 = if cfg!(target_endian = "little") { 0 } else { 8 };
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Inline {
+    data: [u8; INLINE_CAP],
+    /// Also the tag, shared with the last byte of `Heap::cap`.
+    len: u8,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Heap {
+    ptr: NonNull<u8>,
+    len: usize,
+    /// Capacity and tag.
+    cap: usize,
+}
+
+#[repr(C)]
+union Repr {
+    inline: Inline,
+    heap: Heap,
+}
+
+pub struct SsoString {
+    repr: Repr,
+}
+
+/// Heap form owning the buffer of `s`. If the capacity doesn't fit,
+/// panics, dropping `s`.
+fn heap_repr(s: String) -> Repr {
+    assert!(s.capacity() <= MAX_CAP, "capacity overflow");
+    let mut s = ManuallyDrop::new(s);
+    Repr {
+        heap: Heap {
+            // SAFETY: String's pointer is never null. It's taken from the
+            // Vec, as a pointer from the str only covers its length, not
+            // the whole buffer.
+            ptr: unsafe { NonNull::new_unchecked(s.as_mut_vec().as_mut_ptr()) },
+            len: s.len(),
+            cap: (s.capacity() << CAP_SHIFT) | (HEAP_TAG << TAG_SHIFT),
+        },
+    }
+}
+
+/// Empty inline form.
+const EMPTY: Repr = Repr {
+    inline: Inline {
+        data: [0; INLINE_CAP],
+        len: 0,
+    },
+};
+
+const _: () = assert!(mem::size_of::<SsoString>() == 3 * WORD);
+
+// SAFETY: owns its buffer, like String
+unsafe impl Send for SsoString {}
+unsafe impl Sync for SsoString {}
+
+impl SsoString {
+    pub const fn new() -> Self {
+        Self { repr: EMPTY }
+    }
+
+    /// Creates an empty string with room for `cap` bytes.
+    pub fn with_capacity(cap: usize) -> Self {
+        if cap <= INLINE_CAP {
+            Self::new()
+        } else {
+            Self::from_heap(String::with_capacity(cap))
+        }
+    }
+
+    /// Takes over the buffer of `s`, whatever its length.
+    fn from_heap(s: String) -> Self {
+        Self { repr: heap_repr(s) }
+    }
+
+    /// Whether the string is stored inline, without allocation.
+    pub fn is_inline(&self) -> bool {
+        // SAFETY: the tag byte is initialized in both forms
+        unsafe { self.repr.inline.len as usize <= INLINE_CAP }
+    }
+
+    pub fn len(&self) -> usize {
+        // SAFETY: form checked by the tag
+        unsafe {
+            if self.is_inline() {
+                self.repr.inline.len as usize
+            } else {
+                self.repr.heap.len
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        if self.is_inline() {
+            INLINE_CAP
+        } else {
+            // SAFETY: form checked by the tag
+            unsafe { (self.repr.heap.cap >> CAP_SHIFT) & MAX_CAP }
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // SAFETY: form checked by the tag, the first `len` bytes are
+        // valid UTF-8 in both forms
+        unsafe {
+            let bytes = if self.is_inline() {
+                let inline = &self.repr.inline;
+                inline.data.get_unchecked(..inline.len as usize)
+            } else {
+                let heap = &self.repr.heap;
+                std::slice::from_raw_parts(heap.ptr.as_ptr(), heap.len)
+            };
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: same as in `as_str`
+        unsafe {
+            let bytes = if self.is_inline() {
+                let inline = &mut self.repr.inline;
+                inline.data.get_unchecked_mut(..inline.len as usize)
+            } else {
+                let heap = &mut self.repr.heap;
+                std::slice::from_raw_parts_mut(heap.ptr.as_ptr(), heap.len)
+            };
+            str::from_utf8_unchecked_mut(bytes)
+        }
+    }
+
+    /// Moves the heap buffer out as a `String`, leaving the string empty
+    /// and inline. Must be on the heap.
+    fn take_string(&mut self) -> String {
+        debug_assert!(!self.is_inline());
+        let cap = self.capacity();
+        // SAFETY: the parts came from a String, which from now on is the
+        // only owner of the buffer
+        let s = unsafe {
+            let heap = &self.repr.heap;
+            String::from_raw_parts(heap.ptr.as_ptr(), heap.len, cap)
+        };
+        // unions have no drop glue, so this doesn't drop the buffer
+        self.repr = EMPTY;
+        s
+    }
+
+    /// Runs `f` on the heap buffer as a `String`. Must be on the heap.
+    /// While `f` runs, `self` doesn't own the buffer, so if anything
+    /// panics, the buffer is dropped once, and `self` is left empty.
+    fn with_string<R>(&mut self, f: impl FnOnce(&mut String) -> R) -> R {
+        let mut s = self.take_string();
+        let r = f(&mut s);
+        self.repr = heap_repr(s);
+        r
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        if !self.is_inline() {
+            let (len, cap) = (self.len(), self.capacity());
+            let new_len = len
+                .checked_add(s.len())
+                .filter(|&n| n <= MAX_CAP)
+                .expect("capacity overflow");
+            self.with_string(|heap| {
+                // grow like String would, but not past MAX_CAP
+                if new_len > cap {
+                    heap.reserve_exact(new_len.max(2 * cap).min(MAX_CAP) - len);
+                }
+                heap.push_str(s);
+            });
+            return;
+        }
+        let len = self.len();
+        let new_len = len + s.len();
+        if new_len <= INLINE_CAP {
+            // SAFETY: form checked by the tag
+            let inline = unsafe { &mut self.repr.inline };
+            inline.data[len..new_len].copy_from_slice(s.as_bytes());
+            inline.len = new_len as u8;
+        } else {
+            let mut heap = String::with_capacity(new_len.max(2 * INLINE_CAP));
+            heap.push_str(self.as_str());
+            heap.push_str(s);
+            // old value is inline, nothing to drop
+            self.repr = heap_repr(heap);
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Empties the string, keeping the heap buffer if there is one.
+    pub fn clear(&mut self) {
+        if self.is_inline() {
+            self.repr.inline.len = 0;
+        } else {
+            self.repr.heap.len = 0;
+        }
+    }
+}
+
+impl Drop for SsoString {
+    fn drop(&mut self) {
+        if !self.is_inline() {
+            drop(self.take_string());
+        }
+    }
+}
+
+impl Default for SsoString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SsoString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl DerefMut for SsoString {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl Borrow<str> for SsoString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SsoString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Clone for SsoString {
+    fn clone(&self) -> Self {
+        Self::from(self.as_str())
+    }
+}
+
+impl From<&str> for SsoString {
+    fn from(s: &str) -> Self {
+        let mut sso = Self::with_capacity(s.len());
+        sso.push_str(s);
+        sso
+    }
+}
+
+impl From<String> for SsoString {
+    /// Short strings are moved inline, long ones keep their buffer.
+    fn from(s: String) -> Self {
+        if s.len() <= INLINE_CAP {
+            Self::from(s.as_str())
+        } else {
+            Self::from_heap(s)
+        }
+    }
+}
+
+impl From<SsoString> for String {
+    /// Heap strings give up their buffer, inline ones are copied.
+    fn from(s: SsoString) -> Self {
+        if s.is_inline() {
+            return s.as_str().to_owned();
+        }
+        let mut s = s;
+        s.take_string()
+    }
+}
+
+impl FromIterator<char> for SsoString {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl Extend<char> for SsoString {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        for c in iter {
+            self.push(c);
+        }
+    }
+}
+
+impl<'a> Extend<&'a str> for SsoString {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(s);
+        }
+    }
+}
+
+impl fmt::Write for SsoString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SsoString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SsoString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for SsoString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SsoString {}
+
+impl PartialEq<str> for SsoString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SsoString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for SsoString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SsoString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SsoString {
+    /// Same as for `str`, as `Borrow<str>` requires.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
@@ -0,0 +1,119 @@
+//! Both forms of `SsoString` against `String`. Also meant to be run under
+//! Miri: `cargo +nightly miri test -p sso-string`.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::mem;
+
+use sso_string::SsoString;
+
+const INLINE_CAP: usize = 3 * mem::size_of::<usize>() - 1;
+
+#[test]
+fn inline_up_to_cap() {
+    let mut s = SsoString::new();
+    for i in 0..INLINE_CAP {
+        assert!(s.is_inline());
+        assert_eq!(s.len(), i);
+        s.push('a');
+    }
+    assert!(s.is_inline());
+    assert_eq!(s.capacity(), INLINE_CAP);
+    assert_eq!(s, "a".repeat(INLINE_CAP).as_str());
+    s.push('b');
+    assert!(!s.is_inline());
+    assert_eq!(s.len(), INLINE_CAP + 1);
+    assert_eq!(s, format!("{}b", "a".repeat(INLINE_CAP)).as_str());
+}
+
+#[test]
+fn spill_on_multibyte_char() {
+    let mut s = SsoString::from("x".repeat(INLINE_CAP - 1).as_str());
+    assert!(s.is_inline());
+    s.push('é');
+    assert!(!s.is_inline());
+    assert_eq!(s.len(), INLINE_CAP + 1);
+    assert!(s.ends_with('é'));
+}
+
+#[test]
+fn same_as_string() {
+    let mut sso = SsoString::new();
+    let mut std = String::new();
+    for i in 0..2000 {
+        let piece = ["", "a", "bc", "ĉ", "defghijklmnopqrstuvwxyz0"][i % 5];
+        sso.push_str(piece);
+        std.push_str(piece);
+        assert_eq!(sso.as_str(), std);
+        assert!(sso.capacity() >= sso.len());
+    }
+    sso.make_ascii_uppercase();
+    std.make_ascii_uppercase();
+    assert_eq!(sso.as_str(), std);
+}
+
+#[test]
+fn string_round_trips() {
+    for len in [0, 1, INLINE_CAP, INLINE_CAP + 1, 100] {
+        let std = "q".repeat(len);
+        let sso = SsoString::from(std.clone());
+        assert_eq!(sso.is_inline(), len <= INLINE_CAP);
+        assert_eq!(sso, std.as_str());
+        assert_eq!(String::from(sso), std);
+    }
+}
+
+#[test]
+fn long_string_keeps_buffer() {
+    let std = "w".repeat(100);
+    let ptr = std.as_ptr();
+    let sso = SsoString::from(std);
+    assert_eq!(sso.as_ptr(), ptr);
+    let back = String::from(sso);
+    assert_eq!(back.as_ptr(), ptr);
+    assert_eq!(back.len(), 100);
+}
+
+#[test]
+fn clear_keeps_capacity() {
+    let mut s = SsoString::with_capacity(100);
+    assert!(!s.is_inline());
+    let cap = s.capacity();
+    assert!(cap >= 100);
+    s.push_str("hello");
+    s.clear();
+    assert!(s.is_empty());
+    assert_eq!(s.capacity(), cap);
+    assert!(!s.is_inline());
+    s.push_str("again");
+    assert_eq!(s, "again");
+
+    let mut s = SsoString::from("short");
+    s.clear();
+    assert!(s.is_empty() && s.is_inline());
+}
+
+#[test]
+fn clone_and_drop() {
+    for len in [0, 5, INLINE_CAP, INLINE_CAP + 1, 1000] {
+        let a = SsoString::from("z".repeat(len).as_str());
+        let b = a.clone();
+        drop(a);
+        assert_eq!(b.len(), len);
+        assert_eq!(b.is_inline(), len <= INLINE_CAP);
+        let c = b.clone();
+        assert_eq!(b, c);
+    }
+}
+
+#[test]
+fn traits() {
+    let mut s: SsoString = "ab".chars().collect();
+    s.extend(["cd", "ef"]);
+    write!(s, "{}", 42).unwrap();
+    assert_eq!(format!("{}|{:?}", s, s), "abcdef42|\"abcdef42\"");
+    let set: HashSet<SsoString> = ["x", "y"].into_iter().map(SsoString::from).collect();
+    assert!(set.contains("x"));
+    let (a, b) = (SsoString::from("a"), SsoString::from("b"));
+    assert!(a < b);
+}
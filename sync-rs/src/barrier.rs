@@ -0,0 +1,102 @@
+//! Barrier: threads wait until a fixed number of them have arrived.
+//!
+//! Arrivals are counted in an atomic, waiters sleep on a futex over a
+//! generation number. The last thread to arrive is the leader: it resets
+//! the count, bumps the generation and wakes everyone. The barrier can
+//! be used again right away, as threads compare against the generation
+//! they arrived in, not against the count, so a fast thread arriving for
+//! the next round can't confuse the ones still waking from the last.
+//!
+//! A cyclic barrier also runs an action on the leader once per round,
+//! before anyone is released, e.g. to merge results of a phase.
+//!
+//! See https://en.wikipedia.org/wiki/Barrier_(computer_science)
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::futex;
+
+type Action = Box<dyn FnMut() + Send>;
+
+pub struct Barrier {
+    n: u32,
+    arrived: AtomicU32,
+    generation: AtomicU32,
+    action: UnsafeCell<Option<Action>>,
+}
+
+// SAFETY: only the leader calls the action, while all other threads of
+// its round are waiting and the next round can't start
+unsafe impl Sync for Barrier {}
+
+/// Tells one thread per round it was the leader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+impl Barrier {
+    /// Barrier for `n` threads. A barrier for 0 threads acts as for 1.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(1).try_into().expect("too many threads"),
+            arrived: AtomicU32::new(0),
+            generation: AtomicU32::new(0),
+            action: UnsafeCell::new(None),
+        }
+    }
+
+    /// Cyclic barrier that runs `action` on the leader each round before
+    /// releasing the others.
+    pub fn with_action(n: usize, action: impl FnMut() + Send + 'static) -> Self {
+        let mut b = Self::new(n);
+        *b.action.get_mut() = Some(Box::new(action));
+        b
+    }
+
+    /// Blocks until `n` threads have called `wait`. Exactly one of them
+    /// gets a leader result.
+    ///
+    /// If the action panics, the round is still completed, so the other
+    /// threads are not stuck, and the panic goes on in the leader.
+    pub fn wait(&self) -> BarrierWaitResult {
+        let gen = self.generation.load(Ordering::Acquire);
+        if self.arrived.fetch_add(1, Ordering::AcqRel) + 1 < self.n {
+            while self.generation.load(Ordering::Acquire) == gen {
+                futex::wait(&self.generation, gen);
+            }
+            return BarrierWaitResult(false);
+        }
+
+        let _release = Release(self);
+        // SAFETY: see `Sync` impl
+        if let Some(action) = unsafe { &mut *self.action.get() } {
+            action();
+        }
+        BarrierWaitResult(true)
+    }
+}
+
+/// Starts the next round and wakes waiters of this one when dropped.
+struct Release<'a>(&'a Barrier);
+
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        let b = self.0;
+        b.arrived.store(0, Ordering::Relaxed);
+        b.generation.fetch_add(1, Ordering::Release);
+        futex::wake_all(&b.generation);
+    }
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Barrier").field("n", &self.n).finish_non_exhaustive()
+    }
+}
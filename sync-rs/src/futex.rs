@@ -0,0 +1,128 @@
+//! Futex: sleep until a 32-bit atomic changes.
+//!
+//! `wait` puts the thread to sleep only if the atomic still holds the
+//! expected value, checked by the kernel atomically with going to sleep,
+//! so a wake between the caller's check and the sleep can't be missed.
+//! Wakeups can be spurious, callers must check the value again. A wait
+//! can also be given a timeout.
+//!
+//! On Linux this is the futex syscall, called through libc's `syscall`
+//! which std links anyway. The syscall number differs per architecture,
+//! only x86, x86-64, ARM, AArch64 and RISC-V 64 are known here. Elsewhere
+//! waiting just yields the thread, which is correct given spurious
+//! wakeups, only wasteful.
+//!
+//! See https://man7.org/linux/man-pages/man2/futex.2.html
+
+use std::sync::atomic::AtomicU32;
+use std::time::Duration;
+
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "arm"
+    )
+))]
+mod imp {
+    use std::ffi::c_long;
+    use std::ptr;
+    use std::sync::atomic::AtomicU32;
+    use std::time::Duration;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_FUTEX: c_long = 202;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_FUTEX: c_long = 98;
+    #[cfg(any(target_arch = "x86", target_arch = "arm"))]
+    const SYS_FUTEX: c_long = 240;
+
+    const FUTEX_WAIT_PRIVATE: c_long = 128;
+    const FUTEX_WAKE_PRIVATE: c_long = 1 | 128;
+
+    /// Both fields are `long` on all the architectures above.
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    extern "C" {
+        fn syscall(num: c_long, ...) -> c_long;
+    }
+
+    pub fn wait(atomic: &AtomicU32, expected: u32, timeout: Option<Duration>) {
+        let timeout = timeout.map(|t| Timespec {
+            tv_sec: t.as_secs().try_into().unwrap_or(c_long::MAX),
+            tv_nsec: t.subsec_nanos() as c_long,
+        });
+        let timeout = timeout
+            .as_ref()
+            .map_or(ptr::null(), |t| t as *const Timespec);
+        // SAFETY: futex reads the atomic and the timeout, if not null,
+        // both outlive the call
+        unsafe {
+            syscall(
+                SYS_FUTEX,
+                atomic.as_ptr(),
+                FUTEX_WAIT_PRIVATE,
+                expected as c_long,
+                timeout,
+            );
+        }
+    }
+
+    pub fn wake(atomic: &AtomicU32, n: i32) {
+        // SAFETY: wake doesn't access memory
+        unsafe {
+            syscall(SYS_FUTEX, atomic.as_ptr(), FUTEX_WAKE_PRIVATE, n as c_long);
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "x86",
+        target_arch = "arm"
+    )
+)))]
+mod imp {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    pub fn wait(atomic: &AtomicU32, expected: u32, _timeout: Option<Duration>) {
+        if atomic.load(Ordering::Relaxed) == expected {
+            thread::yield_now();
+        }
+    }
+
+    pub fn wake(_atomic: &AtomicU32, _n: i32) {}
+}
+
+/// Sleeps while `atomic` is `expected`, may return spuriously.
+pub fn wait(atomic: &AtomicU32, expected: u32) {
+    imp::wait(atomic, expected, None);
+}
+
+/// Same as `wait`, but sleeps for `timeout` at most.
+pub fn wait_timeout(atomic: &AtomicU32, expected: u32, timeout: Duration) {
+    imp::wait(atomic, expected, Some(timeout));
+}
+
+/// Wakes one thread waiting on `atomic`.
+pub fn wake_one(atomic: &AtomicU32) {
+    imp::wake(atomic, 1);
+}
+
+/// Wakes all threads waiting on `atomic`.
+pub fn wake_all(atomic: &AtomicU32) {
+    imp::wake(atomic, i32::MAX);
+}
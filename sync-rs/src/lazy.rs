@@ -0,0 +1,85 @@
+//! Value computed on first access, safe to share between threads.
+//!
+//! The initializer runs under a `Once`: the first thread to access the
+//! value runs it, others wait until it's done.
+//!
+//! If the initializer panics, the lock is poisoned: the panic propagates
+//! and every access after it, including threads already waiting, panics
+//! too, since there is no value and no initializer to run again.
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::ops::Deref;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+use crate::Once;
+
+pub struct LazyLock<T, F = fn() -> T> {
+    once: Once,
+    init: UnsafeCell<Option<F>>,
+    value: UnsafeCell<Option<T>>,
+}
+
+// SAFETY: the value is shared between threads, `F` is called on one of
+// them, the once orders access to both
+unsafe impl<T: Send + Sync, F: Send> Sync for LazyLock<T, F> {}
+
+// a panicking initializer poisons the lock, so no broken value is seen
+impl<T: RefUnwindSafe + UnwindSafe, F: UnwindSafe> RefUnwindSafe for LazyLock<T, F> {}
+
+impl<T, F: FnOnce() -> T> LazyLock<T, F> {
+    pub const fn new(f: F) -> Self {
+        Self {
+            once: Once::new(),
+            init: UnsafeCell::new(Some(f)),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Initializes the value if needed and returns it. Panics if the
+    /// lock is poisoned.
+    pub fn force(this: &Self) -> &T {
+        // forced, so that waiters woken by a panic of the initializer
+        // report this lock's poisoning rather than the once's
+        this.once.call_once_force(|state| {
+            if state.is_poisoned() {
+                panic!("LazyLock instance has previously been poisoned");
+            }
+            // SAFETY: only the thread running the once touches `init` and
+            // `value`
+            let f = unsafe { (*this.init.get()).take().unwrap() };
+            let value = f();
+            // SAFETY: same as above
+            unsafe { *this.value.get() = Some(value) };
+        });
+        // SAFETY: value is never written after the once completed
+        unsafe { (*this.value.get()).as_ref().unwrap() }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for LazyLock<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        LazyLock::force(self)
+    }
+}
+
+impl<T: Default> Default for LazyLock<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for LazyLock<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut d = f.debug_tuple("LazyLock");
+        if self.once.is_completed() {
+            // SAFETY: value is never written after the once completed
+            d.field(unsafe { (*self.value.get()).as_ref().unwrap() });
+        } else {
+            d.field(&format_args!("<uninit>"));
+        }
+        d.finish()
+    }
+}
@@ -0,0 +1,18 @@
+//! Synchronization primitives built on atomics and futexes.
+//!
+//! See https://marabos.nl/atomics/
+
+mod barrier;
+mod futex;
+mod lazy;
+mod once;
+mod once_lock;
+mod parker;
+mod wait_group;
+
+pub use barrier::{Barrier, BarrierWaitResult};
+pub use lazy::LazyLock;
+pub use once::{Once, OnceState};
+pub use once_lock::OnceLock;
+pub use parker::{Parker, Unparker};
+pub use wait_group::{WaitGroup, WaitGroupGuard};
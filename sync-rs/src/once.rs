@@ -0,0 +1,148 @@
+//! Once: run initialization exactly once, even if called concurrently.
+//!
+//! The first thread to call runs the closure, others sleep on a futex
+//! until it's done. The state goes from `INCOMPLETE` to `RUNNING`, then
+//! `COMPLETE`. Sleepers move `RUNNING` to `WAITING` first, so that the
+//! running thread knows whether a wake syscall is needed at all.
+//!
+//! If the closure panics, the once is poisoned and threads already
+//! waiting are woken. `call_once` panics on a poisoned once, while
+//! `call_once_force` runs its closure again, telling it about the poison.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::futex;
+
+const INCOMPLETE: u32 = 0;
+const POISONED: u32 = 1;
+const RUNNING: u32 = 2;
+const WAITING: u32 = 3;
+const COMPLETE: u32 = 4;
+
+pub struct Once {
+    state: AtomicU32,
+}
+
+/// State passed to the closure of `call_once_force`.
+#[derive(Debug)]
+pub struct OnceState {
+    poisoned: bool,
+}
+
+impl OnceState {
+    /// Whether an earlier closure panicked.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Once {
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(INCOMPLETE),
+        }
+    }
+
+    /// Whether some closure has completed. Once this returns true, the
+    /// effects of the closure are visible to the caller.
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    /// Runs `f` unless some closure has completed already. Returns when
+    /// one has, possibly on another thread. Panics if the once is
+    /// poisoned.
+    pub fn call_once(&self, f: impl FnOnce()) {
+        if self.is_completed() {
+            return;
+        }
+        let mut f = Some(f);
+        self.call(false, &mut |_| f.take().unwrap()());
+    }
+
+    /// Same as `call_once`, but runs `f` on a poisoned once too.
+    pub fn call_once_force(&self, f: impl FnOnce(&OnceState)) {
+        if self.is_completed() {
+            return;
+        }
+        let mut f = Some(f);
+        self.call(true, &mut |state| f.take().unwrap()(state));
+    }
+
+    /// Not generic over the closure, so it's compiled once.
+    #[cold]
+    fn call(&self, ignore_poison: bool, f: &mut dyn FnMut(&OnceState)) {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            match state {
+                COMPLETE => return,
+                POISONED if !ignore_poison => {
+                    panic!("Once instance has previously been poisoned");
+                }
+                INCOMPLETE | POISONED => {
+                    if let Err(s) = self.state.compare_exchange_weak(
+                        state,
+                        RUNNING,
+                        Ordering::Acquire,
+                        Ordering::Acquire,
+                    ) {
+                        state = s;
+                        continue;
+                    }
+                    // poisons the once and wakes waiters if `f` panics
+                    let mut guard = CompletionGuard {
+                        state: &self.state,
+                        set_on_drop: POISONED,
+                    };
+                    f(&OnceState {
+                        poisoned: state == POISONED,
+                    });
+                    guard.set_on_drop = COMPLETE;
+                    return;
+                }
+                RUNNING => {
+                    match self.state.compare_exchange_weak(
+                        RUNNING,
+                        WAITING,
+                        Ordering::Acquire,
+                        Ordering::Acquire,
+                    ) {
+                        Ok(_) => state = WAITING,
+                        Err(s) => state = s,
+                    }
+                }
+                _ => {
+                    futex::wait(&self.state, WAITING);
+                    state = self.state.load(Ordering::Acquire);
+                }
+            }
+        }
+    }
+}
+
+/// Sets the final state when dropped, waking waiters if there are any.
+struct CompletionGuard<'a> {
+    state: &'a AtomicU32,
+    set_on_drop: u32,
+}
+
+impl Drop for CompletionGuard<'_> {
+    fn drop(&mut self) {
+        if self.state.swap(self.set_on_drop, Ordering::Release) == WAITING {
+            futex::wake_all(self.state);
+        }
+    }
+}
+
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Once").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,134 @@
+//! Parker: block a thread until another one unparks it.
+//!
+//! The parker holds at most one token. `unpark` leaves the token and
+//! `park` consumes it, sleeping until there is one. So an `unpark` that
+//! comes before the `park` isn't lost, the `park` just returns at once,
+//! and several `unpark`s in a row still release one `park` only.
+//!
+//! The state is a futex: `EMPTY`, `NOTIFIED` or `PARKED`. `PARKED` is
+//! one below `EMPTY`, so `park` takes the token or announces the sleep
+//! with a single decrement, and `unpark` only makes the wake syscall if
+//! it swapped the state out of `PARKED`.
+//!
+//! The `Parker` belongs to the thread that parks, `Unparker`s are handles
+//! for any thread to wake it.
+//!
+//! See https://docs.rs/crossbeam-utils/latest/crossbeam_utils/sync/struct.Parker.html
+
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::futex;
+
+const EMPTY: u32 = 0;
+const NOTIFIED: u32 = 1;
+const PARKED: u32 = EMPTY.wrapping_sub(1);
+
+pub struct Parker {
+    unparker: Unparker,
+    // one thread parks at a time, but the parker may move between threads
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+#[derive(Clone)]
+pub struct Unparker {
+    state: Arc<AtomicU32>,
+}
+
+impl Default for Parker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parker {
+    pub fn new() -> Self {
+        Self {
+            unparker: Unparker {
+                state: Arc::new(AtomicU32::new(EMPTY)),
+            },
+            _not_sync: PhantomData,
+        }
+    }
+
+    /// Handle that unparks this parker.
+    pub fn unparker(&self) -> Unparker {
+        self.unparker.clone()
+    }
+
+    /// Blocks until the token is available, then consumes it.
+    pub fn park(&self) {
+        let state = &*self.unparker.state;
+        // NOTIFIED -> EMPTY and return, or EMPTY -> PARKED and sleep
+        if state.fetch_sub(1, Ordering::Acquire) == NOTIFIED {
+            return;
+        }
+        loop {
+            futex::wait(state, PARKED);
+            if state
+                .compare_exchange(NOTIFIED, EMPTY, Ordering::Acquire, Ordering::Acquire)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Same as `park`, but gives up after `timeout`. The token, if any,
+    /// is consumed either way.
+    pub fn park_timeout(&self, timeout: Duration) {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.park_deadline(deadline),
+            None => self.park(),
+        }
+    }
+
+    /// Same as `park`, but gives up at `deadline`.
+    pub fn park_deadline(&self, deadline: Instant) {
+        let state = &*self.unparker.state;
+        if state.fetch_sub(1, Ordering::Acquire) == NOTIFIED {
+            return;
+        }
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            futex::wait_timeout(state, PARKED, deadline - now);
+            if state
+                .compare_exchange(NOTIFIED, EMPTY, Ordering::Acquire, Ordering::Acquire)
+                .is_ok()
+            {
+                return;
+            }
+        }
+        // timed out, but an unpark may have just come, take it along
+        state.swap(EMPTY, Ordering::Acquire);
+    }
+}
+
+impl Unparker {
+    /// Makes the token available, waking the parked thread if there is
+    /// one.
+    pub fn unpark(&self) {
+        if self.state.swap(NOTIFIED, Ordering::Release) == PARKED {
+            futex::wake_one(&self.state);
+        }
+    }
+}
+
+impl fmt::Debug for Parker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Parker").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for Unparker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Unparker").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,91 @@
+//! Wait group: wait for a changing number of tasks to finish.
+//!
+//! The group counts pending tasks: `add` raises the count when tasks are
+//! started, `done` lowers it as each one finishes, and `wait` sleeps on a
+//! futex over the count until it's zero. Only the `done` bringing the
+//! count to zero wakes the waiters.
+//!
+//! The group is a handle to shared state, clones refer to the same group,
+//! so it can be moved into spawned threads. A guard calls `done` when
+//! dropped, also when its task panics.
+//!
+//! See https://pkg.go.dev/sync#WaitGroup
+
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crate::futex;
+
+#[derive(Clone, Default)]
+pub struct WaitGroup {
+    count: Arc<AtomicU32>,
+}
+
+impl WaitGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `n` pending tasks.
+    pub fn add(&self, n: u32) {
+        // a failed check leaves the count as it was
+        let res = self
+            .count
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |c| c.checked_add(n));
+        assert!(res.is_ok(), "too many tasks in wait group");
+    }
+
+    /// Marks one task as finished.
+    pub fn done(&self) {
+        // a failed check leaves the count at zero, not wrapped around
+        let res = self
+            .count
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |c| c.checked_sub(1));
+        let Ok(old) = res else {
+            panic!("wait group done more times than tasks added");
+        };
+        if old == 1 {
+            futex::wake_all(&self.count);
+        }
+    }
+
+    /// Adds a task that is done when the returned guard is dropped.
+    pub fn guard(&self) -> WaitGroupGuard {
+        self.add(1);
+        WaitGroupGuard { wg: self.clone() }
+    }
+
+    /// Blocks until there are no pending tasks.
+    pub fn wait(&self) {
+        loop {
+            let n = self.count.load(Ordering::Acquire);
+            if n == 0 {
+                return;
+            }
+            futex::wait(&self.count, n);
+        }
+    }
+
+    pub fn pending(&self) -> u32 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for WaitGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WaitGroup").field("pending", &self.pending()).finish()
+    }
+}
+
+/// Pending task of a wait group, done when dropped.
+#[derive(Debug)]
+pub struct WaitGroupGuard {
+    wg: WaitGroup,
+}
+
+impl Drop for WaitGroupGuard {
+    fn drop(&mut self) {
+        self.wg.done();
+    }
+}
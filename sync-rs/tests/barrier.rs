@@ -0,0 +1,63 @@
+//! `Barrier` releases threads in rounds with one leader each.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use sync::Barrier;
+
+const THREADS: usize = 8;
+const ROUNDS: usize = 100;
+
+#[test]
+fn rounds_do_not_overlap() {
+    let barrier = Barrier::new(THREADS);
+    let arrived = AtomicUsize::new(0);
+    let leaders = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                for round in 0..ROUNDS {
+                    arrived.fetch_add(1, Ordering::Relaxed);
+                    if barrier.wait().is_leader() {
+                        leaders.fetch_add(1, Ordering::Relaxed);
+                    }
+                    // everyone of this round arrived, nobody of the next
+                    // one can pass the second barrier yet
+                    assert!(arrived.load(Ordering::Relaxed) >= (round + 1) * THREADS);
+                    barrier.wait();
+                }
+            });
+        }
+    });
+    assert_eq!(leaders.load(Ordering::Relaxed), ROUNDS);
+}
+
+#[test]
+fn action_runs_once_per_round() {
+    let rounds = Arc::new(AtomicUsize::new(0));
+    let barrier = Barrier::with_action(THREADS, {
+        let rounds = rounds.clone();
+        move || {
+            rounds.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                for round in 0..ROUNDS {
+                    barrier.wait();
+                    assert!(rounds.load(Ordering::Relaxed) > round);
+                }
+            });
+        }
+    });
+    assert_eq!(rounds.load(Ordering::Relaxed), ROUNDS);
+}
+
+#[test]
+fn single_thread_is_leader() {
+    let barrier = Barrier::new(1);
+    assert!(barrier.wait().is_leader());
+    assert!(barrier.wait().is_leader());
+}
@@ -0,0 +1,53 @@
+//! `LazyLock` runs its initializer once, or poisons itself if it panics.
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use sync::LazyLock;
+
+#[test]
+fn initializes_once() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static LAZY: LazyLock<Vec<usize>> = LazyLock::new(|| {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        thread::sleep(Duration::from_millis(50));
+        (0..100).collect()
+    });
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| assert_eq!(LAZY.len(), 100));
+        }
+    });
+    assert_eq!(LAZY[99], 99);
+    assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn captures_environment() {
+    let v = [1, 2, 3];
+    let lazy = LazyLock::new(move || v.iter().sum::<i32>());
+    assert_eq!(format!("{:?}", lazy), "LazyLock(<uninit>)");
+    assert_eq!(*lazy, 6);
+    assert_eq!(format!("{:?}", lazy), "LazyLock(6)");
+}
+
+#[test]
+fn poisoned_by_panic() {
+    static LAZY: LazyLock<i32> = LazyLock::new(|| {
+        thread::sleep(Duration::from_millis(50));
+        panic!("init failed")
+    });
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..4).map(|_| s.spawn(|| *LAZY)).collect();
+        for h in handles {
+            assert!(h.join().is_err());
+        }
+    });
+    let err = panic::catch_unwind(|| *LAZY).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<&str>(),
+        Some(&"LazyLock instance has previously been poisoned")
+    );
+}
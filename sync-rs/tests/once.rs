@@ -0,0 +1,76 @@
+//! `Once` and `OnceLock` run initialization once; a panic in it poisons
+//! `Once` but leaves `OnceLock` empty.
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use sync::{Once, OnceLock};
+
+#[test]
+fn once_runs_once() {
+    let once = Once::new();
+    let calls = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                once.call_once(|| {
+                    thread::sleep(Duration::from_millis(20));
+                    calls.fetch_add(1, Ordering::Relaxed);
+                });
+                // waiters return only after the closure is done
+                assert_eq!(calls.load(Ordering::Relaxed), 1);
+            });
+        }
+    });
+    assert!(once.is_completed());
+}
+
+#[test]
+fn once_poisoned_by_panic() {
+    let once = Once::new();
+    let r = panic::catch_unwind(|| once.call_once(|| panic!("init failed")));
+    assert!(r.is_err());
+    assert!(!once.is_completed());
+    let err = panic::catch_unwind(|| once.call_once(|| {})).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<&str>(),
+        Some(&"Once instance has previously been poisoned")
+    );
+    once.call_once_force(|state| assert!(state.is_poisoned()));
+    assert!(once.is_completed());
+    once.call_once(|| unreachable!());
+}
+
+#[test]
+fn once_lock_initialized_by_one_thread() {
+    let cell = OnceLock::new();
+    let winners = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for i in 0..8 {
+            let (cell, winners) = (&cell, &winners);
+            s.spawn(move || {
+                if cell.set(i).is_ok() {
+                    winners.fetch_add(1, Ordering::Relaxed);
+                }
+                assert!(cell.get().is_some());
+            });
+        }
+    });
+    assert_eq!(winners.load(Ordering::Relaxed), 1);
+    assert_eq!(cell.get_or_init(|| 100), cell.get().unwrap());
+}
+
+#[test]
+fn once_lock_retries_after_panic() {
+    let cell = OnceLock::new();
+    let r = panic::catch_unwind(|| cell.get_or_init(|| panic!("init failed")));
+    assert!(r.is_err());
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.get_or_init(|| 1), &1);
+    let mut cell = cell;
+    assert_eq!(cell.take(), Some(1));
+    assert_eq!(cell.set(2), Ok(()));
+    assert_eq!(format!("{:?}", cell), "OnceLock(2)");
+}
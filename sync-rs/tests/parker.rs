@@ -0,0 +1,76 @@
+//! `Parker` keeps one token, so unparks are never lost but don't pile up.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sync::Parker;
+
+#[test]
+fn unpark_before_park_is_kept() {
+    let parker = Parker::new();
+    parker.unparker().unpark();
+    // returns at once, consuming the token
+    parker.park();
+}
+
+#[test]
+fn unparks_do_not_accumulate() {
+    let parker = Parker::new();
+    let unparker = parker.unparker();
+    unparker.unpark();
+    unparker.unpark();
+    parker.park();
+    let start = Instant::now();
+    parker.park_timeout(Duration::from_millis(50));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn unpark_wakes_parked_thread() {
+    let parker = Parker::new();
+    let unparker = parker.unparker();
+    let ready = Arc::new(AtomicBool::new(false));
+    let ready2 = ready.clone();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        ready2.store(true, Ordering::Relaxed);
+        unparker.unpark();
+    });
+    // wakeups are never spurious, a returned park means an unpark came
+    parker.park();
+    assert!(ready.load(Ordering::Relaxed));
+    t.join().unwrap();
+}
+
+#[test]
+fn park_timeout_returns_early_on_unpark() {
+    let parker = Parker::new();
+    let unparker = parker.unparker();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        unparker.unpark();
+    });
+    let start = Instant::now();
+    parker.park_timeout(Duration::from_secs(10));
+    assert!(start.elapsed() < Duration::from_secs(10));
+    t.join().unwrap();
+}
+
+#[test]
+fn ping_pong() {
+    let (a, b) = (Parker::new(), Parker::new());
+    let (ua, ub) = (a.unparker(), b.unparker());
+    let t = thread::spawn(move || {
+        for _ in 0..1000 {
+            b.park();
+            ua.unpark();
+        }
+    });
+    for _ in 0..1000 {
+        ub.unpark();
+        a.park();
+    }
+    t.join().unwrap();
+}
@@ -0,0 +1,84 @@
+//! `WaitGroup` waits for all added tasks, however they signal being done.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use sync::WaitGroup;
+
+#[test]
+fn waits_for_done_calls() {
+    let wg = WaitGroup::new();
+    let finished = Arc::new(AtomicUsize::new(0));
+    wg.add(8);
+    for _ in 0..8 {
+        let wg = wg.clone();
+        let finished = finished.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            finished.fetch_add(1, Ordering::Relaxed);
+            wg.done();
+        });
+    }
+    wg.wait();
+    assert_eq!(finished.load(Ordering::Relaxed), 8);
+}
+
+#[test]
+fn guards_added_by_tasks() {
+    let wg = WaitGroup::new();
+    let finished = Arc::new(AtomicUsize::new(0));
+    let guard = wg.guard();
+    let (wg2, finished2) = (wg.clone(), finished.clone());
+    thread::spawn(move || {
+        let _guard = guard;
+        // tasks spawning more tasks before they are done themselves
+        for _ in 0..4 {
+            let guard = wg2.guard();
+            let finished = finished2.clone();
+            thread::spawn(move || {
+                let _guard = guard;
+                thread::sleep(Duration::from_millis(10));
+                finished.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    });
+    wg.wait();
+    assert_eq!(finished.load(Ordering::Relaxed), 4);
+    assert_eq!(wg.pending(), 0);
+}
+
+#[test]
+fn guard_is_done_on_panic() {
+    let wg = WaitGroup::new();
+    let guard = wg.guard();
+    let handle = thread::spawn(move || {
+        let _guard = guard;
+        panic!("task failed");
+    });
+    wg.wait();
+    assert!(handle.join().is_err());
+}
+
+#[test]
+fn done_without_add() {
+    let wg = WaitGroup::new();
+    let res = std::panic::catch_unwind(|| wg.done());
+    let msg = res.unwrap_err().downcast::<&str>().unwrap();
+    assert!(msg.contains("done more times"), "{}", msg);
+    assert_eq!(wg.pending(), 0);
+    // the group still works
+    wg.add(1);
+    wg.done();
+    wg.wait();
+}
+
+#[test]
+fn overflowing_add_keeps_count() {
+    let wg = WaitGroup::new();
+    wg.add(u32::MAX);
+    let res = std::panic::catch_unwind(|| wg.add(1));
+    assert!(res.is_err());
+    assert_eq!(wg.pending(), u32::MAX);
+}
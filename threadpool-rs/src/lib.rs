@@ -0,0 +1,135 @@
+//! Fixed number of worker threads running queued tasks.
+//!
+//! Tasks are boxed closures put into a queue guarded by a mutex. Idle
+//! workers sleep on a condition variable until a task arrives. A task
+//! that panics doesn't take its worker down: the panic is caught and
+//! counted, and the worker goes on with the next task.
+//!
+//! Dropping the pool is a graceful shutdown: tasks already queued still
+//! run, then workers exit and are joined.
+
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+struct State {
+    tasks: VecDeque<Task>,
+    /// Tasks being run right now.
+    running: usize,
+    panicked: usize,
+    shutdown: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Signaled when a task is queued or the pool shuts down.
+    task_ready: Condvar,
+    /// Signaled when the last task finishes.
+    idle: Condvar,
+}
+
+impl Shared {
+    /// Tasks run outside the lock and panics are caught, so the mutex
+    /// can't be poisoned.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+}
+
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    pub fn new(threads: usize) -> Self {
+        assert!(threads > 0, "thread pool needs at least one thread");
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                tasks: VecDeque::new(),
+                running: 0,
+                panicked: 0,
+                shutdown: false,
+            }),
+            task_ready: Condvar::new(),
+            idle: Condvar::new(),
+        });
+        let workers = (0..threads)
+            .map(|i| {
+                let shared = shared.clone();
+                thread::Builder::new()
+                    .name(format!("pool-worker-{}", i))
+                    .spawn(move || work(&shared))
+                    .expect("failed to spawn worker thread")
+            })
+            .collect();
+        Self { shared, workers }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queues `f` to run on some worker.
+    pub fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.shared.lock().tasks.push_back(Box::new(f));
+        self.shared.task_ready.notify_one();
+    }
+
+    /// Blocks until the queue is empty and no task is running. Tasks may
+    /// be queued from other threads, or by tasks themselves, meanwhile.
+    pub fn join(&self) {
+        let mut state = self.shared.lock();
+        while !state.tasks.is_empty() || state.running > 0 {
+            state = self.shared.idle.wait(state).unwrap();
+        }
+    }
+
+    /// Number of tasks that panicked so far.
+    pub fn panic_count(&self) -> usize {
+        self.shared.lock().panicked
+    }
+}
+
+fn work(shared: &Shared) {
+    let mut state = shared.lock();
+    loop {
+        let Some(task) = state.tasks.pop_front() else {
+            if state.shutdown {
+                return;
+            }
+            state = shared.task_ready.wait(state).unwrap();
+            continue;
+        };
+        state.running += 1;
+        drop(state);
+
+        let ok = panic::catch_unwind(AssertUnwindSafe(task)).is_ok();
+
+        state = shared.lock();
+        state.running -= 1;
+        if !ok {
+            state.panicked += 1;
+        }
+        if state.running == 0 && state.tasks.is_empty() {
+            shared.idle.notify_all();
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.task_ready.notify_all();
+        for worker in self.workers.drain(..) {
+            // workers catch task panics, so they don't panic themselves
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Tasks all run, `join` waits for them, panics stay inside their tasks.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use threadpool::ThreadPool;
+
+#[test]
+fn join_waits_for_all_tasks() {
+    let pool = ThreadPool::new(4);
+    let done = Arc::new(AtomicUsize::new(0));
+    for _ in 0..100 {
+        let done = done.clone();
+        pool.execute(move || {
+            thread::sleep(Duration::from_millis(1));
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    pool.join();
+    assert_eq!(done.load(Ordering::Relaxed), 100);
+}
+
+#[test]
+fn panics_are_isolated() {
+    let pool = ThreadPool::new(2);
+    let done = Arc::new(AtomicUsize::new(0));
+    for i in 0..10 {
+        let done = done.clone();
+        pool.execute(move || {
+            if i % 2 == 0 {
+                panic!("task {} failed", i);
+            }
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    pool.join();
+    assert_eq!(done.load(Ordering::Relaxed), 5);
+    assert_eq!(pool.panic_count(), 5);
+}
+
+#[test]
+fn drop_runs_queued_tasks() {
+    let done = Arc::new(AtomicUsize::new(0));
+    {
+        let pool = ThreadPool::new(1);
+        for _ in 0..10 {
+            let done = done.clone();
+            pool.execute(move || {
+                thread::sleep(Duration::from_millis(5));
+                done.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    }
+    assert_eq!(done.load(Ordering::Relaxed), 10);
+}